@@ -0,0 +1,81 @@
+// SPDX-License-Identifier: Apache-2.0
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Token {
+    Auto(String),
+    AllowHotplug(String),
+    Source(String),
+    SourceDirectory(String),
+    /// `iface <name> <inet|inet6> <static|manual|dhcp|loopback>`
+    Iface {
+        name: String,
+        family: String,
+        method: String,
+    },
+    /// An indented `key value...` line belonging to the last `iface` block.
+    Attribute(String, String),
+    Comment,
+    Blank,
+}
+
+// A small hand-rolled lexer: ifupdown stanza files are line oriented, so we
+// tokenize one line at a time rather than building a full char-level state
+// machine.
+pub(crate) fn tokenize(content: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    for raw_line in content.lines() {
+        let line = raw_line.trim_end();
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            tokens.push(Token::Blank);
+            continue;
+        }
+        if trimmed.starts_with('#') {
+            tokens.push(Token::Comment);
+            continue;
+        }
+        let mut parts = trimmed.split_whitespace();
+        let Some(keyword) = parts.next() else {
+            tokens.push(Token::Blank);
+            continue;
+        };
+        match keyword {
+            "auto" => {
+                for name in parts {
+                    tokens.push(Token::Auto(name.to_string()));
+                }
+            }
+            "allow-hotplug" => {
+                for name in parts {
+                    tokens.push(Token::AllowHotplug(name.to_string()));
+                }
+            }
+            "source" => {
+                if let Some(path) = parts.next() {
+                    tokens.push(Token::Source(path.to_string()));
+                }
+            }
+            "source-directory" => {
+                if let Some(path) = parts.next() {
+                    tokens.push(Token::SourceDirectory(path.to_string()));
+                }
+            }
+            "iface" => {
+                let name = parts.next().unwrap_or_default().to_string();
+                let family = parts.next().unwrap_or("inet").to_string();
+                let method = parts.next().unwrap_or("static").to_string();
+                tokens.push(Token::Iface {
+                    name,
+                    family,
+                    method,
+                });
+            }
+            // Indented option line, e.g. `address 192.0.2.1`.
+            key => {
+                let value = parts.collect::<Vec<&str>>().join(" ");
+                tokens.push(Token::Attribute(key.to_string(), value));
+            }
+        }
+    }
+    tokens
+}
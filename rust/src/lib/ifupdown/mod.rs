@@ -0,0 +1,361 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Backend for reading and writing Debian/ifupdown style
+//! `/etc/network/interfaces` files, for hosts that are not managed by
+//! NetworkManager.
+
+mod lexer;
+mod parser;
+mod render;
+mod retrieve;
+
+pub(crate) use self::parser::ifupdown_parse_str;
+pub(crate) use self::render::ifupdown_render;
+pub(crate) use self::retrieve::ifupdown_retrieve;
+
+use crate::{
+    BaseInterface, BondConfig, BondInterface, BondMode, BondOptions,
+    EthernetInterface, Interface, InterfaceIpAddrv4, InterfaceIpAddrv6,
+    InterfaceIpv4, InterfaceIpv6, InterfaceState, InterfaceType,
+    LinuxBridgeConfig, LinuxBridgeInterface, LinuxBridgeOptions,
+    LinuxBridgePortConfig, NetworkState, NmstateError, RouteEntry, Routes,
+};
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct IfaceStanza {
+    pub(crate) name: String,
+    pub(crate) auto: bool,
+    pub(crate) family: AddressFamily,
+    pub(crate) method: Method,
+    pub(crate) options: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum AddressFamily {
+    #[default]
+    Inet,
+    Inet6,
+}
+
+// A `source`/`source-directory` include found while parsing one file,
+// resolved and recursed into by `retrieve::ifupdown_retrieve`.
+#[derive(Debug, Clone)]
+pub(crate) enum Include {
+    Source(String),
+    SourceDirectory(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum Method {
+    #[default]
+    Manual,
+    Static,
+    Dhcp,
+    Loopback,
+}
+
+impl std::str::FromStr for Method {
+    type Err = NmstateError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "static" => Ok(Self::Static),
+            "manual" => Ok(Self::Manual),
+            "dhcp" => Ok(Self::Dhcp),
+            "loopback" => Ok(Self::Loopback),
+            _ => Err(NmstateError::new(
+                crate::ErrorKind::InvalidArgument,
+                format!("Unsupported ifupdown method '{s}'"),
+            )),
+        }
+    }
+}
+
+// `bond-mode` may be spelled out or given as its numeric kernel value.
+pub(crate) fn bond_mode_from_ifupdown_str(
+    value: &str,
+) -> Option<BondMode> {
+    match value {
+        "balance-rr" | "0" => Some(BondMode::RoundRobin),
+        "active-backup" | "1" => Some(BondMode::ActiveBackup),
+        "balance-xor" | "2" => Some(BondMode::XOR),
+        "broadcast" | "3" => Some(BondMode::Broadcast),
+        "802.3ad" | "4" => Some(BondMode::LACP),
+        "balance-tlb" | "5" => Some(BondMode::TLB),
+        "balance-alb" | "6" => Some(BondMode::ALB),
+        _ => None,
+    }
+}
+
+pub(crate) fn bond_mode_to_ifupdown_str(mode: &BondMode) -> &'static str {
+    match mode {
+        BondMode::RoundRobin => "balance-rr",
+        BondMode::ActiveBackup => "active-backup",
+        BondMode::XOR => "balance-xor",
+        BondMode::Broadcast => "broadcast",
+        BondMode::LACP => "802.3ad",
+        BondMode::TLB => "balance-tlb",
+        BondMode::ALB => "balance-alb",
+        _ => "balance-rr",
+    }
+}
+
+// Fold the stanzas for a single `iface` name (it may appear up to twice,
+// once per address family) into one nmstate `Interface`.
+pub(crate) fn stanzas_to_iface(
+    name: &str,
+    autostart: bool,
+    stanzas: &[&IfaceStanza],
+) -> Result<Interface, NmstateError> {
+    let mut base = BaseInterface::new();
+    base.name = name.to_string();
+    base.state = if autostart {
+        InterfaceState::Up
+    } else {
+        InterfaceState::Down
+    };
+
+    let mut bond_ports: Option<Vec<String>> = None;
+    let mut bond_mode: Option<BondMode> = None;
+    let mut bond_xmit_hash_policy: Option<String> = None;
+    let mut bridge_ports: Option<Vec<String>> = None;
+    let mut bridge_vlan_aware = false;
+
+    for stanza in stanzas {
+        if stanza.method == Method::Loopback {
+            base.iface_type = InterfaceType::Loopback;
+        }
+        let mut ip = match stanza.family {
+            AddressFamily::Inet => InterfaceIpv4::new(),
+            AddressFamily::Inet6 => {
+                let mut ip6 = InterfaceIpv6::new();
+                ip6.enabled = true;
+                apply_stanza_to_ipv6(&mut ip6, stanza)?;
+                base.ipv6 = Some(ip6);
+                continue;
+            }
+        };
+        ip.enabled = true;
+        apply_stanza_to_ipv4(&mut ip, stanza)?;
+        base.ipv4 = Some(ip);
+
+        for (key, value) in &stanza.options {
+            match key.as_str() {
+                "mtu" => {
+                    base.mtu = value.parse::<u64>().ok();
+                }
+                "bond-slaves" => {
+                    bond_ports = Some(
+                        value
+                            .split_whitespace()
+                            .map(|s| s.to_string())
+                            .collect(),
+                    );
+                }
+                "bond-mode" => {
+                    bond_mode = bond_mode_from_ifupdown_str(value);
+                }
+                "bond-xmit-hash-policy" => {
+                    bond_xmit_hash_policy = Some(value.clone());
+                }
+                "bridge_ports" => {
+                    bridge_ports = Some(
+                        value
+                            .split_whitespace()
+                            .map(|s| s.to_string())
+                            .collect(),
+                    );
+                }
+                "bridge_vlan_aware" => {
+                    bridge_vlan_aware = value == "yes";
+                }
+                _ => {
+                    // Options this backend does not otherwise recognize
+                    // are dropped here: neither `IfaceStanza` nor the
+                    // nmstate `Interface` model it folds into has a slot
+                    // to carry an arbitrary ifupdown option through to
+                    // `ifupdown_render`, so there is nothing to stash
+                    // them in yet.
+                }
+            }
+        }
+    }
+
+    if let Some(ports) = bond_ports {
+        base.iface_type = InterfaceType::Bond;
+        let mut iface = BondInterface::new();
+        iface.base = base;
+        let options = if bond_mode.is_some() || bond_xmit_hash_policy.is_some()
+        {
+            Some(BondOptions {
+                mode: bond_mode,
+                xmit_hash_policy: bond_xmit_hash_policy,
+                ..Default::default()
+            })
+        } else {
+            None
+        };
+        iface.bond = Some(BondConfig {
+            port: Some(ports),
+            options,
+            mode: bond_mode,
+            ..Default::default()
+        });
+        return Ok(Interface::Bond(Box::new(iface)));
+    }
+
+    if let Some(ports) = bridge_ports {
+        base.iface_type = InterfaceType::LinuxBridge;
+        let mut iface = LinuxBridgeInterface::new();
+        iface.base = base;
+        iface.bridge = Some(LinuxBridgeConfig {
+            options: Some(LinuxBridgeOptions {
+                vlan_protocol: None,
+                vlan_filtering: Some(bridge_vlan_aware),
+                ..Default::default()
+            }),
+            port: Some(
+                ports
+                    .into_iter()
+                    .map(|name| LinuxBridgePortConfig {
+                        name,
+                        ..Default::default()
+                    })
+                    .collect(),
+            ),
+        });
+        return Ok(Interface::LinuxBridge(Box::new(iface)));
+    }
+
+    if base.iface_type == InterfaceType::Loopback {
+        let mut iface = crate::LoopbackInterface::new();
+        iface.base = base;
+        return Ok(Interface::Loopback(Box::new(iface)));
+    }
+
+    base.iface_type = InterfaceType::Ethernet;
+    let mut iface = EthernetInterface::new();
+    iface.base = base;
+    Ok(Interface::Ethernet(Box::new(iface)))
+}
+
+fn apply_stanza_to_ipv4(
+    ip: &mut InterfaceIpv4,
+    stanza: &IfaceStanza,
+) -> Result<(), NmstateError> {
+    if stanza.method == Method::Dhcp {
+        ip.dhcp = Some(true);
+        return Ok(());
+    }
+    let mut address = None;
+    let mut prefix_len = None;
+    for (key, value) in &stanza.options {
+        match key.as_str() {
+            "address" => address = Some(value.clone()),
+            "netmask" => {
+                prefix_len = netmask_to_prefix_len(value);
+            }
+            _ => {}
+        }
+    }
+    if let Some(address) = address {
+        ip.addresses = Some(vec![InterfaceIpAddrv4 {
+            ip: address,
+            prefix_length: prefix_len.unwrap_or(32),
+            ..Default::default()
+        }]);
+    }
+    Ok(())
+}
+
+fn apply_stanza_to_ipv6(
+    ip: &mut InterfaceIpv6,
+    stanza: &IfaceStanza,
+) -> Result<(), NmstateError> {
+    if stanza.method == Method::Dhcp {
+        ip.dhcp = Some(true);
+        return Ok(());
+    }
+    let mut address = None;
+    let mut prefix_len = None;
+    for (key, value) in &stanza.options {
+        match key.as_str() {
+            "address" => address = Some(value.clone()),
+            "netmask" => prefix_len = value.parse::<u8>().ok(),
+            _ => {}
+        }
+    }
+    if let Some(address) = address {
+        ip.addresses = Some(vec![InterfaceIpAddrv6 {
+            ip: address,
+            prefix_length: prefix_len.unwrap_or(64),
+            ..Default::default()
+        }]);
+    }
+    Ok(())
+}
+
+fn netmask_to_prefix_len(netmask: &str) -> Option<u8> {
+    let addr: std::net::Ipv4Addr = netmask.parse().ok()?;
+    Some(u32::from(addr).count_ones() as u8)
+}
+
+// Inverse of `netmask_to_prefix_len`: ifupdown's `netmask` keyword expects
+// a dotted-decimal netmask, not the bare prefix length `address`/CIDR
+// notation uses elsewhere.
+pub(crate) fn prefix_len_to_netmask(prefix_len: u8) -> String {
+    let mask: u32 = if prefix_len == 0 {
+        0
+    } else {
+        u32::MAX << (32 - u32::from(prefix_len))
+    };
+    std::net::Ipv4Addr::from(mask).to_string()
+}
+
+fn gateway_route(stanza: &IfaceStanza, iface_name: &str) -> Option<RouteEntry> {
+    let gateway = stanza
+        .options
+        .iter()
+        .find(|(k, _)| k == "gateway")
+        .map(|(_, v)| v.clone())?;
+    let mut route = RouteEntry::new();
+    route.destination = Some(match stanza.family {
+        AddressFamily::Inet => "0.0.0.0/0".to_string(),
+        AddressFamily::Inet6 => "::/0".to_string(),
+    });
+    route.next_hop_addr = Some(gateway);
+    route.next_hop_iface = Some(iface_name.to_string());
+    Some(route)
+}
+
+pub(crate) fn stanzas_to_network_state(
+    stanzas: &[IfaceStanza],
+) -> Result<NetworkState, NmstateError> {
+    let mut net_state = NetworkState::new();
+    let mut names: Vec<&str> = Vec::new();
+    for stanza in stanzas {
+        if !names.contains(&stanza.name.as_str()) {
+            names.push(&stanza.name);
+        }
+    }
+
+    let mut config_routes = Vec::new();
+    for name in names {
+        let grouped: Vec<&IfaceStanza> =
+            stanzas.iter().filter(|s| s.name == name).collect();
+        let autostart = grouped.iter().any(|s| s.auto);
+        let iface = stanzas_to_iface(name, autostart, grouped.as_slice())?;
+        for stanza in &grouped {
+            if let Some(route) = gateway_route(stanza, name) {
+                config_routes.push(route);
+            }
+        }
+        net_state.append_interface_data(iface);
+    }
+    if !config_routes.is_empty() {
+        net_state.routes = Some(Routes {
+            config: Some(config_routes),
+            running: None,
+        });
+    }
+    Ok(net_state)
+}
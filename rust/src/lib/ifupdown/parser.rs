@@ -0,0 +1,149 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashSet;
+
+use crate::{ErrorKind, NmstateError};
+
+use super::lexer::{tokenize, Token};
+use super::{AddressFamily, IfaceStanza, Include, Method};
+
+// Collects the `source`/`source-directory` includes of a single file,
+// left unresolved for `retrieve::ifupdown_retrieve` to expand against the
+// filesystem.
+pub(crate) fn ifupdown_parse_includes(content: &str) -> Vec<Include> {
+    tokenize(content)
+        .into_iter()
+        .filter_map(|token| match token {
+            Token::Source(path) => Some(Include::Source(path)),
+            Token::SourceDirectory(path) => Some(Include::SourceDirectory(path)),
+            _ => None,
+        })
+        .collect()
+}
+
+// Groups option lines under their owning `iface` block and folds `auto`
+// lines into an autostart set. `source`/`source-directory` includes are
+// returned to the caller unresolved, so the caller (which knows the
+// filesystem root) can recurse.
+pub(crate) fn ifupdown_parse_str(
+    content: &str,
+) -> Result<Vec<IfaceStanza>, NmstateError> {
+    let tokens = tokenize(content);
+
+    let mut autostart: HashSet<String> = HashSet::new();
+    let mut stanzas: Vec<IfaceStanza> = Vec::new();
+    let mut seen_families: HashSet<(String, String)> = HashSet::new();
+    let mut current: Option<IfaceStanza> = None;
+
+    for token in tokens {
+        match token {
+            Token::Auto(name) | Token::AllowHotplug(name) => {
+                autostart.insert(name);
+            }
+            Token::Source(_) | Token::SourceDirectory(_) => {
+                // Resolving includes is the responsibility of the caller,
+                // who has filesystem access; nothing to do here.
+            }
+            Token::Iface {
+                name,
+                family,
+                method,
+            } => {
+                if let Some(stanza) = current.take() {
+                    stanzas.push(stanza);
+                }
+                let family_key = (name.clone(), family.clone());
+                if !seen_families.insert(family_key) {
+                    return Err(NmstateError::new(
+                        ErrorKind::InvalidArgument,
+                        format!(
+                            "Duplicate ifupdown `iface {name} {family}` \
+                            stanza: a method may only be set once per \
+                            address family"
+                        ),
+                    ));
+                }
+                let family = match family.as_str() {
+                    "inet" => AddressFamily::Inet,
+                    "inet6" => AddressFamily::Inet6,
+                    other => {
+                        return Err(NmstateError::new(
+                            ErrorKind::InvalidArgument,
+                            format!(
+                                "Unsupported ifupdown address family \
+                                '{other}' for interface {name}"
+                            ),
+                        ));
+                    }
+                };
+                let method = method.parse::<Method>()?;
+                current = Some(IfaceStanza {
+                    name,
+                    auto: false,
+                    family,
+                    method,
+                    options: Vec::new(),
+                });
+            }
+            Token::Attribute(key, value) => {
+                if let Some(stanza) = current.as_mut() {
+                    stanza.options.push((key, value));
+                }
+                // An option line outside of any `iface` block is ignored,
+                // matching ifupdown's own lenient parser.
+            }
+            Token::Comment | Token::Blank => {}
+        }
+    }
+    if let Some(stanza) = current.take() {
+        stanzas.push(stanza);
+    }
+
+    for stanza in stanzas.iter_mut() {
+        stanza.auto = autostart.contains(&stanza.name);
+    }
+
+    Ok(stanzas)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_static_stanza_round_trips_options() {
+        let content = "\
+auto eth0
+iface eth0 inet static
+    address 192.0.2.1
+    netmask 255.255.255.0
+    gateway 192.0.2.254
+";
+        let stanzas = ifupdown_parse_str(content).unwrap();
+        assert_eq!(stanzas.len(), 1);
+        let stanza = &stanzas[0];
+        assert_eq!(stanza.name, "eth0");
+        assert!(stanza.auto);
+        assert_eq!(stanza.family, AddressFamily::Inet);
+        assert_eq!(stanza.method, Method::Static);
+        assert_eq!(
+            stanza.options,
+            vec![
+                ("address".to_string(), "192.0.2.1".to_string()),
+                ("netmask".to_string(), "255.255.255.0".to_string()),
+                ("gateway".to_string(), "192.0.2.254".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_duplicate_family_stanza() {
+        let content = "\
+iface eth0 inet static
+    address 192.0.2.1
+iface eth0 inet static
+    address 192.0.2.2
+";
+        assert!(ifupdown_parse_str(content).is_err());
+    }
+}
@@ -0,0 +1,168 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fmt::Write;
+
+use crate::{Interface, InterfaceType, NetworkState};
+
+use super::{bond_mode_to_ifupdown_str, prefix_len_to_netmask};
+
+// Render a desired `NetworkState` into `/etc/network/interfaces` stanza
+// text. This is the inverse of `ifupdown_parse_str`/`stanzas_to_iface`; it
+// only covers the subset of the model ifupdown itself can express.
+pub(crate) fn ifupdown_render(net_state: &NetworkState) -> String {
+    let mut out = String::new();
+    for iface in net_state
+        .interfaces
+        .kernel_ifaces
+        .values()
+        .chain(net_state.interfaces.user_ifaces.values())
+    {
+        if iface.is_absent() {
+            continue;
+        }
+        if iface.is_up() {
+            let _ = writeln!(out, "auto {}", iface.name());
+        }
+        render_iface(&mut out, iface);
+    }
+    out
+}
+
+fn render_iface(out: &mut String, iface: &Interface) {
+    let base = iface.base_iface();
+    let method = if base
+        .ipv4
+        .as_ref()
+        .and_then(|ip| ip.dhcp)
+        .unwrap_or(false)
+    {
+        "dhcp"
+    } else if iface.iface_type() == InterfaceType::Loopback {
+        "loopback"
+    } else if base
+        .ipv4
+        .as_ref()
+        .and_then(|ip| ip.addresses.as_ref())
+        .map(|a| !a.is_empty())
+        .unwrap_or(false)
+    {
+        "static"
+    } else {
+        "manual"
+    };
+    let _ = writeln!(out, "iface {} inet {}", iface.name(), method);
+
+    if let Some(ip) = base.ipv4.as_ref() {
+        if let Some(addrs) = ip.addresses.as_ref() {
+            if let Some(addr) = addrs.first() {
+                let _ = writeln!(out, "    address {}", addr.ip);
+                let _ = writeln!(
+                    out,
+                    "    netmask {}",
+                    prefix_len_to_netmask(addr.prefix_length)
+                );
+            }
+        }
+    }
+    if let Some(mtu) = base.mtu {
+        let _ = writeln!(out, "    mtu {mtu}");
+    }
+
+    match iface {
+        Interface::Bond(bond) => {
+            if let Some(bond_conf) = bond.bond.as_ref() {
+                if let Some(ports) = bond_conf.port.as_ref() {
+                    let _ =
+                        writeln!(out, "    bond-slaves {}", ports.join(" "));
+                }
+                if let Some(mode) = bond_conf.mode.as_ref() {
+                    let _ = writeln!(
+                        out,
+                        "    bond-mode {}",
+                        bond_mode_to_ifupdown_str(mode)
+                    );
+                }
+            }
+        }
+        Interface::LinuxBridge(br) => {
+            if let Some(br_conf) = br.bridge.as_ref() {
+                if let Some(ports) = br_conf.port.as_ref() {
+                    let names: Vec<&str> =
+                        ports.iter().map(|p| p.name.as_str()).collect();
+                    let _ = writeln!(
+                        out,
+                        "    bridge_ports {}",
+                        names.join(" ")
+                    );
+                }
+            }
+        }
+        _ => {}
+    }
+    out.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        EthernetInterface, InterfaceIpAddrv4, InterfaceIpv4, InterfaceState,
+    };
+
+    use super::super::{stanzas_to_iface, Method};
+
+    fn eth_with_static_addr(
+        name: &str,
+        ip: &str,
+        prefix_length: u8,
+    ) -> Interface {
+        let mut iface = EthernetInterface::new();
+        iface.base.name = name.to_string();
+        iface.base.state = InterfaceState::Up;
+        let mut ipv4 = InterfaceIpv4::new();
+        ipv4.enabled = true;
+        ipv4.addresses = Some(vec![InterfaceIpAddrv4 {
+            ip: ip.to_string(),
+            prefix_length,
+            ..Default::default()
+        }]);
+        iface.base.ipv4 = Some(ipv4);
+        Interface::Ethernet(Box::new(iface))
+    }
+
+    #[test]
+    fn test_render_iface_writes_dotted_decimal_netmask() {
+        let iface = eth_with_static_addr("eth0", "192.0.2.1", 24);
+        let mut out = String::new();
+
+        render_iface(&mut out, &iface);
+
+        assert!(out.contains("address 192.0.2.1"));
+        assert!(out.contains("netmask 255.255.255.0"));
+        assert!(!out.contains("netmask 24"));
+    }
+
+    #[test]
+    fn test_render_iface_prefix_length_round_trips_through_parser() {
+        let iface = eth_with_static_addr("eth0", "192.0.2.1", 25);
+        let mut out = String::new();
+        render_iface(&mut out, &iface);
+
+        let stanzas = super::super::parser::ifupdown_parse_str(&out)
+            .expect("rendered stanza should parse back");
+        assert_eq!(stanzas.len(), 1);
+        assert_eq!(stanzas[0].method, Method::Static);
+
+        let parsed_iface =
+            stanzas_to_iface("eth0", true, &[&stanzas[0]]).unwrap();
+        let addr = parsed_iface
+            .base_iface()
+            .ipv4
+            .as_ref()
+            .and_then(|ip| ip.addresses.as_ref())
+            .and_then(|addrs| addrs.first())
+            .expect("address should round-trip");
+        assert_eq!(addr.ip, "192.0.2.1");
+        assert_eq!(addr.prefix_length, 25);
+    }
+}
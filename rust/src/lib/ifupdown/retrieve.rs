@@ -0,0 +1,227 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::{ErrorKind, NetworkState, NmstateError};
+
+use super::parser::{ifupdown_parse_includes, ifupdown_parse_str};
+use super::{stanzas_to_network_state, IfaceStanza, Include};
+
+const DEFAULT_IFUPDOWN_INTERFACES_FILE: &str = "/etc/network/interfaces";
+
+/// Parse `/etc/network/interfaces` (or `path`, when given) together with
+/// any `source`/`source-directory` includes it pulls in, and fold the
+/// result into a [`NetworkState`]. This is the import-side counterpart of
+/// `ifupdown_render`.
+pub(crate) fn ifupdown_retrieve(
+    path: Option<&str>,
+) -> Result<NetworkState, NmstateError> {
+    let root = PathBuf::from(path.unwrap_or(DEFAULT_IFUPDOWN_INTERFACES_FILE));
+    let mut stanzas: Vec<IfaceStanza> = Vec::new();
+    let mut visited: Vec<PathBuf> = Vec::new();
+    load_file(&root, &mut stanzas, &mut visited)?;
+    stanzas_to_network_state(&stanzas)
+}
+
+fn load_file(
+    path: &Path,
+    stanzas: &mut Vec<IfaceStanza>,
+    visited: &mut Vec<PathBuf>,
+) -> Result<(), NmstateError> {
+    // Guard against `source`/`source-directory` cycles.
+    let canon = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    if visited.contains(&canon) {
+        return Ok(());
+    }
+    visited.push(canon);
+
+    let content = fs::read_to_string(path).map_err(|e| {
+        NmstateError::new(
+            ErrorKind::InvalidArgument,
+            format!(
+                "Failed to read ifupdown interfaces file {}: {e}",
+                path.display()
+            ),
+        )
+    })?;
+
+    stanzas.extend(ifupdown_parse_str(&content)?);
+
+    let base_dir = path.parent().unwrap_or_else(|| Path::new("/"));
+    for include in ifupdown_parse_includes(&content) {
+        match include {
+            Include::Source(pattern) => {
+                for included in resolve_glob(base_dir, &pattern) {
+                    load_file(&included, stanzas, visited)?;
+                }
+            }
+            Include::SourceDirectory(pattern) => {
+                for dir in resolve_glob(base_dir, &pattern) {
+                    if !dir.is_dir() {
+                        continue;
+                    }
+                    for entry in source_directory_files(&dir) {
+                        load_file(&entry, stanzas, visited)?;
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+// `source`/`source-directory` patterns are relative to the including
+// file's directory unless already absolute. ifupdown only ever uses `*`
+// wildcards in the wild, so a minimal matcher is enough here.
+fn resolve_glob(base_dir: &Path, pattern: &str) -> Vec<PathBuf> {
+    let pattern_path = if Path::new(pattern).is_absolute() {
+        PathBuf::from(pattern)
+    } else {
+        base_dir.join(pattern)
+    };
+    let Some(file_pattern) =
+        pattern_path.file_name().and_then(|n| n.to_str())
+    else {
+        return Vec::new();
+    };
+    if !file_pattern.contains('*') {
+        return vec![pattern_path];
+    }
+    let dir = pattern_path.parent().unwrap_or_else(|| Path::new("/"));
+    let mut matches: Vec<PathBuf> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .map(|n| glob_match(file_pattern, n))
+                .unwrap_or(false)
+        })
+        .collect();
+    matches.sort();
+    matches
+}
+
+// A minimal `*`-only glob matcher.
+fn glob_match(pattern: &str, name: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return pattern == name;
+    }
+    let mut rest = name;
+    for (i, part) in parts.iter().enumerate() {
+        if part.is_empty() {
+            continue;
+        }
+        if i == 0 {
+            let Some(stripped) = rest.strip_prefix(part) else {
+                return false;
+            };
+            rest = stripped;
+        } else if i == parts.len() - 1 {
+            return rest.ends_with(part);
+        } else if let Some(pos) = rest.find(part) {
+            rest = &rest[pos + part.len()..];
+        } else {
+            return false;
+        }
+    }
+    true
+}
+
+// Matches ifupdown's own `source-directory` filter: only "portable
+// filename" characters, so editor backups (`foo~`) and dotfiles are
+// skipped.
+fn source_directory_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|p| {
+            p.is_file()
+                && p
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .map(is_portable_filename)
+                    .unwrap_or(false)
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+fn is_portable_filename(name: &str) -> bool {
+    !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("ifcfg-*", "ifcfg-eth0"));
+        assert!(glob_match("*.cfg", "eth0.cfg"));
+        assert!(glob_match("eth0", "eth0"));
+        assert!(!glob_match("eth0", "eth1"));
+        assert!(!glob_match("ifcfg-*", "other-eth0"));
+    }
+
+    #[test]
+    fn test_is_portable_filename() {
+        assert!(is_portable_filename("eth0"));
+        assert!(is_portable_filename("eth0_1-2"));
+        assert!(!is_portable_filename(".eth0"));
+        assert!(!is_portable_filename("eth0~"));
+    }
+
+    #[test]
+    fn test_ifupdown_retrieve_follows_source_directory() {
+        let dir = std::env::temp_dir().join(format!(
+            "nmstate-ifupdown-retrieve-test-{}",
+            std::process::id()
+        ));
+        let included_dir = dir.join("interfaces.d");
+        fs::create_dir_all(&included_dir).unwrap();
+
+        fs::write(
+            dir.join("interfaces"),
+            "auto eth0\n\
+             iface eth0 inet static\n    \
+             address 192.0.2.1\n    \
+             netmask 255.255.255.0\n\
+             source-directory interfaces.d/*\n",
+        )
+        .unwrap();
+        fs::write(
+            included_dir.join("eth1"),
+            "auto eth1\niface eth1 inet dhcp\n",
+        )
+        .unwrap();
+
+        let net_state = ifupdown_retrieve(Some(
+            dir.join("interfaces").to_str().unwrap(),
+        ))
+        .unwrap();
+
+        assert!(net_state
+            .interfaces
+            .kernel_ifaces
+            .contains_key("eth0"));
+        assert!(net_state
+            .interfaces
+            .kernel_ifaces
+            .contains_key("eth1"));
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
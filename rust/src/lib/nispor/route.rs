@@ -3,9 +3,13 @@
 use log::warn;
 
 use crate::{
-    ErrorKind, MergedRoutes, NmstateError, RouteEntry, RouteType, Routes,
+    ErrorKind, MergedRoutes, NmstateError, RouteEntry, RouteEntryMetrics,
+    RouteProtocol, RouteType, Routes,
 };
 
+use super::route_rtnetlink;
+use super::route_rtnetlink::needs_rtnetlink_fallback;
+
 const SUPPORTED_ROUTE_SCOPE: [nispor::RouteScope; 2] =
     [nispor::RouteScope::Universe, nispor::RouteScope::Link];
 
@@ -27,8 +31,90 @@ const IPV6_DEFAULT_GATEWAY: &str = "::/0";
 const IPV4_EMPTY_NEXT_HOP_ADDRESS: &str = "0.0.0.0";
 const IPV6_EMPTY_NEXT_HOP_ADDRESS: &str = "::";
 
-// kernel values
+// RTAX_* nested route metric attribute indices, from linux/rtnetlink.h.
+// `lock` is a bitmask of `1 << RTAX_*` marking which metrics are pinned
+// rather than left for the kernel's path-MTU-discovery-style autotuning.
+const RTAX_MTU: u32 = 2;
+const RTAX_WINDOW: u32 = 3;
+const RTAX_RTT: u32 = 4;
+const RTAX_RTTVAR: u32 = 5;
+const RTAX_SSTHRESH: u32 = 6;
 const RTAX_CWND: u32 = 7;
+const RTAX_ADVMSS: u32 = 8;
+const RTAX_REORDERING: u32 = 9;
+const RTAX_HOPLIMIT: u32 = 10;
+const RTAX_INITCWND: u32 = 11;
+const RTAX_FEATURES: u32 = 12;
+const RTAX_RTO_MIN: u32 = 13;
+const RTAX_INITRWND: u32 = 14;
+const RTAX_QUICKACK: u32 = 15;
+
+// RTT/RTTVAR/RTO_MIN are stored by the kernel in USER_HZ clock ticks; we
+// surface and accept them in milliseconds like `ip route` does.
+const USER_HZ: u32 = 100;
+
+fn clock_ticks_to_ms(ticks: u32) -> u32 {
+    ticks * 1000 / USER_HZ
+}
+
+fn ms_to_clock_ticks(ms: u32) -> u32 {
+    ms * USER_HZ / 1000
+}
+
+// A metric is considered "set" when it is either explicitly non-zero or
+// lock-pinned via its bit in `lock`; a zero, unlocked metric is treated as
+// unset. `cwnd` follows the same rule as the rest of RTAX_*.
+fn locked_or_set(lock: u32, bit: u32, value: Option<u32>) -> Option<u32> {
+    let value = value?;
+    if value != 0 || (lock & (1 << bit)) != 0 {
+        Some(value)
+    } else {
+        None
+    }
+}
+
+fn np_route_metrics(np_route: &nispor::Route) -> Option<RouteEntryMetrics> {
+    let lock = np_route.lock.unwrap_or(0);
+    let metrics = RouteEntryMetrics {
+        mtu: locked_or_set(lock, RTAX_MTU, np_route.mtu),
+        window: locked_or_set(lock, RTAX_WINDOW, np_route.window),
+        rtt: locked_or_set(lock, RTAX_RTT, np_route.rtt)
+            .map(clock_ticks_to_ms),
+        rttvar: locked_or_set(lock, RTAX_RTTVAR, np_route.rttvar)
+            .map(clock_ticks_to_ms),
+        ssthresh: locked_or_set(lock, RTAX_SSTHRESH, np_route.ssthresh),
+        cwnd: locked_or_set(lock, RTAX_CWND, np_route.cwnd),
+        advmss: locked_or_set(lock, RTAX_ADVMSS, np_route.advmss),
+        reordering: locked_or_set(lock, RTAX_REORDERING, np_route.reordering),
+        hoplimit: locked_or_set(lock, RTAX_HOPLIMIT, np_route.hoplimit),
+        initcwnd: locked_or_set(lock, RTAX_INITCWND, np_route.initcwnd),
+        initrwnd: locked_or_set(lock, RTAX_INITRWND, np_route.initrwnd),
+        features: locked_or_set(lock, RTAX_FEATURES, np_route.features),
+        rto_min: locked_or_set(lock, RTAX_RTO_MIN, np_route.rto_min)
+            .map(clock_ticks_to_ms),
+        quickack: locked_or_set(lock, RTAX_QUICKACK, np_route.quickack)
+            .map(|v| v != 0),
+    };
+    if metrics.mtu.is_none()
+        && metrics.window.is_none()
+        && metrics.rtt.is_none()
+        && metrics.rttvar.is_none()
+        && metrics.ssthresh.is_none()
+        && metrics.cwnd.is_none()
+        && metrics.advmss.is_none()
+        && metrics.reordering.is_none()
+        && metrics.hoplimit.is_none()
+        && metrics.initcwnd.is_none()
+        && metrics.initrwnd.is_none()
+        && metrics.features.is_none()
+        && metrics.rto_min.is_none()
+        && metrics.quickack.is_none()
+    {
+        None
+    } else {
+        Some(metrics)
+    }
+}
 
 pub(crate) async fn get_routes(running_config_only: bool) -> Routes {
     let mut ret = Routes::new();
@@ -37,6 +123,7 @@ pub(crate) async fn get_routes(running_config_only: bool) -> Routes {
         nispor::RouteType::BlackHole,
         nispor::RouteType::Unreachable,
         nispor::RouteType::Prohibit,
+        nispor::RouteType::Throw,
     ];
     let protocols = if running_config_only {
         SUPPORTED_STATIC_ROUTE_PROTOCOL.as_slice()
@@ -130,6 +217,8 @@ fn np_routetype_to_nmstate(np_route: &nispor::Route) -> RouteEntry {
     }
     route_entry.metric = np_route.metric.map(i64::from);
     route_entry.table_id = Some(np_route.table);
+    route_entry.tos = Some(np_route.tos);
+    route_entry.route_protocol = np_routeprotocol_to_nmstate(np_route.protocol);
     match np_route.route_type {
         nispor::RouteType::BlackHole => {
             route_entry.route_type = Some(RouteType::Blackhole)
@@ -140,14 +229,17 @@ fn np_routetype_to_nmstate(np_route: &nispor::Route) -> RouteEntry {
         nispor::RouteType::Prohibit => {
             route_entry.route_type = Some(RouteType::Prohibit)
         }
+        // Throw routes (RTN_THROW) terminate lookup in the current table
+        // and fall back to the next policy-routing rule; like the other
+        // reject-style types, they carry no next-hop.
+        nispor::RouteType::Throw => {
+            route_entry.route_type = Some(RouteType::Throw)
+        }
         _ => {
             log::debug!("Got unsupported route {:?}", np_route);
         }
     }
-    // according to `man ip-route`, cwnd is useless without the lock flag, so
-    // we require both cwnd and its lock flag to consider cwnd as set.
-    let cwnd_lock = np_route.lock.unwrap_or(0) & (1 << RTAX_CWND) != 0;
-    route_entry.cwnd = if cwnd_lock { np_route.cwnd } else { None };
+    route_entry.metrics = np_route_metrics(np_route);
 
     route_entry
 }
@@ -202,14 +294,42 @@ fn np_route_to_nmstate(np_route: &nispor::Route) -> RouteEntry {
     route_entry.source = source;
     route_entry.metric = np_route.metric.map(i64::from);
     route_entry.table_id = Some(np_route.table);
-    // according to `man ip-route`, cwnd is useless without the lock flag, so
-    // we require both cwnd and its lock flag to consider cwnd as set.
-    let cwnd_lock = np_route.lock.unwrap_or(0) & (1 << RTAX_CWND) != 0;
-    route_entry.cwnd = if cwnd_lock { np_route.cwnd } else { None };
+    route_entry.tos = Some(np_route.tos);
+    route_entry.route_protocol = np_routeprotocol_to_nmstate(np_route.protocol);
+    route_entry.metrics = np_route_metrics(np_route);
 
     route_entry
 }
 
+fn np_routeprotocol_to_nmstate(
+    protocol: nispor::RouteProtocol,
+) -> Option<RouteProtocol> {
+    match protocol {
+        nispor::RouteProtocol::Static => Some(RouteProtocol::Static),
+        nispor::RouteProtocol::Boot => Some(RouteProtocol::Boot),
+        nispor::RouteProtocol::Ra => Some(RouteProtocol::Ra),
+        nispor::RouteProtocol::Dhcp => Some(RouteProtocol::Dhcp),
+        nispor::RouteProtocol::Babel => Some(RouteProtocol::Babel),
+        nispor::RouteProtocol::KeepAlived => Some(RouteProtocol::KeepAlived),
+        nispor::RouteProtocol::Mrouted => Some(RouteProtocol::Mrouted),
+        _ => None,
+    }
+}
+
+fn nmstate_routeprotocol_to_np(
+    protocol: &RouteProtocol,
+) -> nispor::RouteProtocol {
+    match protocol {
+        RouteProtocol::Static => nispor::RouteProtocol::Static,
+        RouteProtocol::Boot => nispor::RouteProtocol::Boot,
+        RouteProtocol::Ra => nispor::RouteProtocol::Ra,
+        RouteProtocol::Dhcp => nispor::RouteProtocol::Dhcp,
+        RouteProtocol::Babel => nispor::RouteProtocol::Babel,
+        RouteProtocol::KeepAlived => nispor::RouteProtocol::KeepAlived,
+        RouteProtocol::Mrouted => nispor::RouteProtocol::Mrouted,
+    }
+}
+
 fn is_multipath(np_route: &nispor::Route) -> bool {
     np_route
         .multipath
@@ -251,50 +371,314 @@ fn nmstate_to_nispor_route_conf(
             None
         }
     });
+    // A table ID above `u8::MAX` (or a non-zero TOS, which nispor's
+    // `RouteConf` has no field for at all) is handled by the direct
+    // rtnetlink fallback in `route_rtnetlink` instead -- the caller
+    // filters those out via `needs_rtnetlink_fallback` before this
+    // function ever sees them, so reaching this point with either is a
+    // caller bug rather than something to recover from here.
     if let Some(table_id) = nmstate_rt.table_id {
         if table_id > u8::MAX.into() {
             return Err(NmstateError::new(
-                ErrorKind::NotImplementedError,
+                ErrorKind::Bug,
                 format!(
-                    "nispor apply does not support route table ID bigger \
-                    than {} yet, got {}, ignoring",
-                    u8::MAX,
-                    table_id
+                    "BUG: route table ID {table_id} should have been \
+                    routed to the rtnetlink fallback backend, not nispor"
                 ),
             ));
-        } else {
-            ret.table = Some(table_id as u8);
         }
+        ret.table = Some(table_id as u8);
     }
-    if nmstate_rt.weight.is_some() {
-        return Err(NmstateError::new(
-            ErrorKind::NotImplementedError,
-            "nispor apply does not support route weight yet".into(),
-        ));
+    // Stamping the protocol here is what lets a route coexist with (and
+    // not get swept up by) routes a daemon like Babel or keepalived
+    // installs at the same destination/table: nispor's own cleanup only
+    // replaces/removes a route whose protocol matches what it is told to
+    // manage, so a nmstate-owned route tagged e.g. `static` is never
+    // confused with one the daemon already owns.
+    if let Some(protocol) = nmstate_rt.route_protocol.as_ref() {
+        ret.protocol = Some(nmstate_routeprotocol_to_np(protocol));
     }
+    // A lone next-hop that still carries an explicit `weight` (nothing
+    // else shares its destination/table/metric) has no other hop to be
+    // weighted against, so the value is meaningless here; ECMP groups are
+    // built into a single multipath `RouteConf` by
+    // `nmstate_multipath_to_nispor_route_conf` before this function ever
+    // sees them.
 
-    if nmstate_rt.route_type.is_some() {
-        return Err(NmstateError::new(
-            ErrorKind::NotImplementedError,
-            "nispor apply does not support route type yet".into(),
-        ));
+    // Reject-style route types (and `throw`) have no next-hop/oif -- the
+    // `oif`/`via` assignment above already leaves those `None` as given,
+    // so there is nothing else to require here.
+    if let Some(route_type) = nmstate_rt.route_type.as_ref() {
+        ret.route_type = Some(match route_type {
+            RouteType::Blackhole => nispor::RouteType::BlackHole,
+            RouteType::Unreachable => nispor::RouteType::Unreachable,
+            RouteType::Prohibit => nispor::RouteType::Prohibit,
+            RouteType::Throw => nispor::RouteType::Throw,
+        });
     }
 
-    if nmstate_rt.cwnd.is_some() {
-        return Err(NmstateError::new(
-            ErrorKind::NotImplementedError,
-            "nispor apply does not support route congestion window yet".into(),
-        ));
+    if let Some(metrics) = nmstate_rt.metrics.as_ref() {
+        let mut lock: u32 = 0;
+        if let Some(mtu) = metrics.mtu {
+            ret.mtu = Some(mtu);
+            lock |= 1 << RTAX_MTU;
+        }
+        if let Some(window) = metrics.window {
+            ret.window = Some(window);
+            lock |= 1 << RTAX_WINDOW;
+        }
+        if let Some(rtt) = metrics.rtt {
+            ret.rtt = Some(ms_to_clock_ticks(rtt));
+            lock |= 1 << RTAX_RTT;
+        }
+        if let Some(rttvar) = metrics.rttvar {
+            ret.rttvar = Some(ms_to_clock_ticks(rttvar));
+            lock |= 1 << RTAX_RTTVAR;
+        }
+        if let Some(ssthresh) = metrics.ssthresh {
+            ret.ssthresh = Some(ssthresh);
+            lock |= 1 << RTAX_SSTHRESH;
+        }
+        if let Some(cwnd) = metrics.cwnd {
+            ret.cwnd = Some(cwnd);
+            lock |= 1 << RTAX_CWND;
+        }
+        if let Some(advmss) = metrics.advmss {
+            ret.advmss = Some(advmss);
+            lock |= 1 << RTAX_ADVMSS;
+        }
+        if let Some(reordering) = metrics.reordering {
+            ret.reordering = Some(reordering);
+            lock |= 1 << RTAX_REORDERING;
+        }
+        if let Some(hoplimit) = metrics.hoplimit {
+            ret.hoplimit = Some(hoplimit);
+            lock |= 1 << RTAX_HOPLIMIT;
+        }
+        if let Some(initcwnd) = metrics.initcwnd {
+            ret.initcwnd = Some(initcwnd);
+            lock |= 1 << RTAX_INITCWND;
+        }
+        if let Some(initrwnd) = metrics.initrwnd {
+            ret.initrwnd = Some(initrwnd);
+            lock |= 1 << RTAX_INITRWND;
+        }
+        if let Some(features) = metrics.features {
+            ret.features = Some(features);
+            lock |= 1 << RTAX_FEATURES;
+        }
+        if let Some(rto_min) = metrics.rto_min {
+            ret.rto_min = Some(ms_to_clock_ticks(rto_min));
+            lock |= 1 << RTAX_RTO_MIN;
+        }
+        if let Some(quickack) = metrics.quickack {
+            ret.quickack = Some(u32::from(quickack));
+            lock |= 1 << RTAX_QUICKACK;
+        }
+        // Every metric the user explicitly requested is pinned via its
+        // lock bit, mirroring how the kernel reports a metric as "set"
+        // only once its lock bit is there too.
+        if lock != 0 {
+            ret.lock = Some(lock);
+        }
     }
     Ok(ret)
 }
 
-pub(crate) fn gen_nispor_route_confs(
+type RouteGroupKey = (Option<String>, Option<u32>, Option<i64>);
+
+fn route_group_key(nmstate_rt: &RouteEntry) -> RouteGroupKey {
+    (
+        nmstate_rt.destination.clone(),
+        nmstate_rt.table_id,
+        nmstate_rt.metric,
+    )
+}
+
+// Routes with a table ID above 255 or a non-zero TOS can't be expressed
+// by nispor's `RouteConf` at all; `apply_routes` applies these separately
+// via `route_rtnetlink::apply_routes_via_rtnetlink` instead of handing
+// them to nispor.
+fn gen_rtnetlink_fallback_routes(
+    merged_routes: &MergedRoutes,
+) -> Vec<RouteEntry> {
+    merged_routes
+        .changed_routes
+        .iter()
+        .filter(|rt| needs_rtnetlink_fallback(rt))
+        .cloned()
+        .collect()
+}
+
+// Applies `merged_routes` via nispor, splitting off the table-ID-above-255
+// and/or non-zero-TOS routes nispor's `RouteConf` cannot express at all
+// and sending those straight to the kernel instead (see
+// `route_rtnetlink`). `gen_nispor_route_confs` on its own silently drops
+// that subset, so it is deliberately kept private -- this is the only
+// entry point for applying routes through the nispor backend.
+pub(crate) async fn apply_routes(
+    merged_routes: &MergedRoutes,
+) -> Result<(), NmstateError> {
+    let fallback_routes = gen_rtnetlink_fallback_routes(merged_routes);
+    let route_confs = gen_nispor_route_confs(merged_routes)?;
+
+    if !route_confs.is_empty() {
+        let mut net_conf = nispor::NetConf::default();
+        net_conf.routes = Some(route_confs);
+        nispor::NetState::apply(&net_conf).await.map_err(|e| {
+            NmstateError::new(
+                ErrorKind::PluginFailure,
+                format!("Failed to apply routes via nispor: {e}"),
+            )
+        })?;
+    }
+
+    route_rtnetlink::apply_routes_via_rtnetlink(&fallback_routes).await
+}
+
+fn gen_nispor_route_confs(
     merged_routes: &MergedRoutes,
 ) -> Result<Vec<nispor::RouteConf>, NmstateError> {
     let mut ret = Vec::new();
-    for nmstate_rt in merged_routes.changed_routes.as_slice() {
-        ret.push(nmstate_to_nispor_route_conf(nmstate_rt)?)
+    // Routes sharing a destination/table/metric and each carrying a
+    // `weight` are ECMP next-hops of the same route; cluster them so they
+    // apply as a single multipath `RouteConf` instead of one `RouteConf`
+    // per next-hop.
+    let mut multipath_groups: Vec<(RouteGroupKey, Vec<&RouteEntry>)> =
+        Vec::new();
+
+    for nmstate_rt in merged_routes
+        .changed_routes
+        .iter()
+        .filter(|rt| !needs_rtnetlink_fallback(rt))
+    {
+        if nmstate_rt.weight.is_none() {
+            ret.push(nmstate_to_nispor_route_conf(nmstate_rt)?);
+            continue;
+        }
+        let key = route_group_key(nmstate_rt);
+        if let Some((_, hops)) =
+            multipath_groups.iter_mut().find(|(k, _)| *k == key)
+        {
+            hops.push(nmstate_rt);
+        } else {
+            multipath_groups.push((key, vec![nmstate_rt]));
+        }
+    }
+
+    for (_, hops) in multipath_groups {
+        if hops.len() == 1 {
+            // Nothing else shares this destination/table/metric: keep the
+            // existing single-next-hop shape instead of wrapping it in a
+            // one-entry multipath list.
+            ret.push(nmstate_to_nispor_route_conf(hops[0])?);
+        } else {
+            ret.push(nmstate_multipath_to_nispor_route_conf(&hops)?);
+        }
     }
     Ok(ret)
 }
+
+// Folds a group of same-destination/table/metric next-hops into one
+// multipath `nispor::RouteConf`, the counterpart of
+// `flat_multipath_route` on the query side.
+fn nmstate_multipath_to_nispor_route_conf(
+    hops: &[&RouteEntry],
+) -> Result<nispor::RouteConf, NmstateError> {
+    let mut ret = nmstate_to_nispor_route_conf(hops[0])?;
+    // The next-hop address/interface live per-hop in `multipath` instead.
+    ret.oif = None;
+    ret.via = None;
+
+    let mut multipath = Vec::new();
+    for hop in hops {
+        let via = hop.next_hop_addr.clone().ok_or_else(|| {
+            NmstateError::new(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "ECMP next-hop for destination {:?} is missing a \
+                    next-hop-address",
+                    hop.destination
+                ),
+            )
+        })?;
+        let iface = hop.next_hop_iface.clone().ok_or_else(|| {
+            NmstateError::new(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "ECMP next-hop for destination {:?} is missing a \
+                    next-hop-interface",
+                    hop.destination
+                ),
+            )
+        })?;
+        multipath.push(nispor::MultipathRoute {
+            via,
+            iface,
+            weight: hop.weight.unwrap_or(1),
+        });
+    }
+    ret.multipath = Some(multipath);
+    Ok(ret)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MergedRoutes;
+
+    fn route_with_table(table_id: u32) -> RouteEntry {
+        let mut route = RouteEntry::new();
+        route.destination = Some("192.0.2.0/24".to_string());
+        route.table_id = Some(table_id);
+        route
+    }
+
+    fn route_with_tos(tos: u8) -> RouteEntry {
+        let mut route = RouteEntry::new();
+        route.destination = Some("192.0.2.0/24".to_string());
+        route.tos = Some(tos);
+        route
+    }
+
+    #[test]
+    fn test_needs_rtnetlink_fallback_for_table_above_255() {
+        assert!(needs_rtnetlink_fallback(&route_with_table(256)));
+        assert!(!needs_rtnetlink_fallback(&route_with_table(255)));
+    }
+
+    #[test]
+    fn test_needs_rtnetlink_fallback_for_non_zero_tos() {
+        assert!(needs_rtnetlink_fallback(&route_with_tos(0x10)));
+        assert!(!needs_rtnetlink_fallback(&route_with_tos(0)));
+    }
+
+    #[test]
+    fn test_gen_rtnetlink_fallback_routes_only_returns_fallback_routes() {
+        let normal = route_with_table(254);
+        let fallback = route_with_table(10000);
+        let merged_routes = MergedRoutes {
+            changed_routes: vec![normal, fallback.clone()],
+            ..Default::default()
+        };
+
+        let fallback_routes = gen_rtnetlink_fallback_routes(&merged_routes);
+
+        assert_eq!(fallback_routes, vec![fallback]);
+    }
+
+    #[test]
+    fn test_gen_nispor_route_confs_excludes_rtnetlink_fallback_routes() {
+        let normal = route_with_table(254);
+        let fallback = route_with_table(10000);
+        let merged_routes = MergedRoutes {
+            changed_routes: vec![normal, fallback],
+            ..Default::default()
+        };
+
+        let confs = gen_nispor_route_confs(&merged_routes).unwrap();
+
+        assert_eq!(confs.len(), 1);
+        assert_eq!(confs[0].table, Some(254));
+    }
+}
@@ -0,0 +1,246 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Direct rtnetlink `RTM_NEWROUTE`/`RTM_DELROUTE` fallback apply backend.
+//!
+//! nispor's `RouteConf.table` is a `u8` and has no TOS field at all, but
+//! policy-routing setups routinely need both: a route table ID above 255
+//! (`ip route add table 10000 ...`) and/or a non-zero TOS/DSCP selector.
+//! Rather than waiting on nispor to grow either, routes needing one are
+//! applied here directly against the kernel, following the same
+//! `RT_TABLE_COMPAT` convention `ip route` itself uses: when the table ID
+//! no longer fits in the route header's one-byte `table` field, that field
+//! is set to `RT_TABLE_COMPAT` and the real ID travels in the `RTA_TABLE`
+//! attribute instead.
+//!
+//! This needs the `rtnetlink`, `tokio`, and `libc` crates, none of which
+//! this checkout's manifest currently depends on -- that dependency
+//! addition needs to go through its own review rather than be invented
+//! here, so this module does not build until it lands. An `ip`-CLI
+//! stopgap was tried in its place, but shelling out on a route-mutating
+//! path traded a missing dependency for stderr-string-matched error
+//! handling and no atomicity, which is worse than waiting on the
+//! manifest update.
+
+use std::net::IpAddr;
+
+use rtnetlink::packet_route::route::{RouteAttribute, RouteMessage};
+use rtnetlink::packet_route::AddressFamily;
+
+use crate::{ErrorKind, NmstateError, RouteEntry};
+
+const RT_TABLE_COMPAT: u8 = 252;
+
+pub(crate) fn needs_rtnetlink_fallback(route: &RouteEntry) -> bool {
+    route.table_id.map(|table_id| table_id > u8::MAX as u32).unwrap_or(false)
+        || route.tos.map(|tos| tos != 0).unwrap_or(false)
+}
+
+pub(crate) async fn apply_routes_via_rtnetlink(
+    routes: &[RouteEntry],
+) -> Result<(), NmstateError> {
+    if routes.is_empty() {
+        return Ok(());
+    }
+    let (connection, handle, _) = rtnetlink::new_connection().map_err(|e| {
+        NmstateError::new(
+            ErrorKind::PluginFailure,
+            format!("Failed to open rtnetlink socket: {e}"),
+        )
+    })?;
+    tokio::spawn(connection);
+
+    for route in routes {
+        let message = route_to_message(route)?;
+        if route.is_absent() {
+            handle.route().del(message).execute().await.map_err(|e| {
+                NmstateError::new(
+                    ErrorKind::PluginFailure,
+                    format!(
+                        "Failed to delete route {:?} table {:?} via \
+                        rtnetlink: {e}",
+                        route.destination, route.table_id
+                    ),
+                )
+            })?;
+        } else {
+            handle
+                .route()
+                .add(message)
+                .replace()
+                .execute()
+                .await
+                .map_err(|e| {
+                    NmstateError::new(
+                        ErrorKind::PluginFailure,
+                        format!(
+                            "Failed to add route {:?} table {:?} via \
+                            rtnetlink: {e}",
+                            route.destination, route.table_id
+                        ),
+                    )
+                })?;
+        }
+    }
+    Ok(())
+}
+
+// Builds the `RouteMessage` for a table-ID/TOS fallback route, covering
+// only the fields such a route actually needs: nispor already owns every
+// other route shape.
+fn route_to_message(route: &RouteEntry) -> Result<RouteMessage, NmstateError> {
+    let dst_str = route.destination.clone().ok_or_else(|| {
+        NmstateError::new(
+            ErrorKind::InvalidArgument,
+            "Route requiring the rtnetlink fallback is missing a \
+            destination"
+                .to_string(),
+        )
+    })?;
+    let (addr_str, prefix_str) =
+        dst_str.split_once('/').unwrap_or((dst_str.as_str(), ""));
+    let dst: IpAddr = addr_str.parse().map_err(|e| {
+        NmstateError::new(
+            ErrorKind::InvalidArgument,
+            format!(
+                "Invalid route destination {dst_str:?} for rtnetlink \
+                apply: {e}"
+            ),
+        )
+    })?;
+    let default_prefix_len = if dst.is_ipv6() { 128 } else { 32 };
+    let prefix_len = if prefix_str.is_empty() {
+        default_prefix_len
+    } else {
+        prefix_str.parse::<u8>().map_err(|e| {
+            NmstateError::new(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "Invalid prefix length in route destination \
+                    {dst_str:?} for rtnetlink apply: {e}"
+                ),
+            )
+        })?
+    };
+
+    let mut message = RouteMessage::default();
+    message.header.address_family = if dst.is_ipv6() {
+        AddressFamily::Inet6
+    } else {
+        AddressFamily::Inet
+    };
+    message.header.destination_prefix_length = prefix_len;
+    message.header.tos = route.tos.unwrap_or(0);
+    message.attributes.push(RouteAttribute::Destination(dst.into()));
+
+    let table_id = route.table_id.unwrap_or(0);
+    if table_id > u8::MAX as u32 {
+        message.header.table = RT_TABLE_COMPAT;
+        message.attributes.push(RouteAttribute::Table(table_id));
+    } else {
+        message.header.table = table_id as u8;
+    }
+
+    if let Some(gateway) = route
+        .next_hop_addr
+        .as_deref()
+        .and_then(|a| a.parse::<IpAddr>().ok())
+    {
+        message.attributes.push(RouteAttribute::Gateway(gateway.into()));
+    }
+    if let Some(iface_name) = route.next_hop_iface.as_deref() {
+        message
+            .attributes
+            .push(RouteAttribute::Oif(if_name_to_index(iface_name)?));
+    }
+    if let Some(metric) =
+        route.metric.and_then(|m| u32::try_from(m).ok())
+    {
+        message.attributes.push(RouteAttribute::Priority(metric));
+    }
+
+    Ok(message)
+}
+
+// `RouteAttribute::Oif` takes a kernel interface index rather than a
+// name; the rest of nmstate deals in names, so resolve it the same way
+// the `ip` tool does -- `if_nametoindex(3)` -- rather than pulling in a
+// whole extra netlink round-trip just to look up one index.
+fn if_name_to_index(iface_name: &str) -> Result<u32, NmstateError> {
+    let c_name = std::ffi::CString::new(iface_name).map_err(|e| {
+        NmstateError::new(
+            ErrorKind::InvalidArgument,
+            format!("Invalid interface name {iface_name:?}: {e}"),
+        )
+    })?;
+    let index = unsafe { libc::if_nametoindex(c_name.as_ptr()) };
+    if index == 0 {
+        Err(NmstateError::new(
+            ErrorKind::InvalidArgument,
+            format!(
+                "Interface {iface_name} does not exist, cannot resolve it \
+                to an index for the rtnetlink route fallback"
+            ),
+        ))
+    } else {
+        Ok(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn route_with_table(table_id: u32) -> RouteEntry {
+        let mut route = RouteEntry::new();
+        route.destination = Some("192.0.2.0/24".to_string());
+        route.table_id = Some(table_id);
+        route
+    }
+
+    fn route_with_tos(tos: u8) -> RouteEntry {
+        let mut route = RouteEntry::new();
+        route.destination = Some("192.0.2.0/24".to_string());
+        route.tos = Some(tos);
+        route
+    }
+
+    #[test]
+    fn test_needs_rtnetlink_fallback_for_table_above_255() {
+        assert!(needs_rtnetlink_fallback(&route_with_table(256)));
+        assert!(!needs_rtnetlink_fallback(&route_with_table(255)));
+    }
+
+    #[test]
+    fn test_needs_rtnetlink_fallback_for_non_zero_tos() {
+        assert!(needs_rtnetlink_fallback(&route_with_tos(0x10)));
+        assert!(!needs_rtnetlink_fallback(&route_with_tos(0)));
+    }
+
+    #[test]
+    fn test_route_to_message_sets_compat_table_above_255() {
+        let route = route_with_table(10000);
+        let message = route_to_message(&route).unwrap();
+        assert_eq!(message.header.table, RT_TABLE_COMPAT);
+        assert!(message
+            .attributes
+            .contains(&RouteAttribute::Table(10000)));
+    }
+
+    #[test]
+    fn test_route_to_message_keeps_table_below_256_in_header() {
+        let route = route_with_table(200);
+        let message = route_to_message(&route).unwrap();
+        assert_eq!(message.header.table, 200);
+        assert!(!message
+            .attributes
+            .iter()
+            .any(|attr| matches!(attr, RouteAttribute::Table(_))));
+    }
+
+    #[test]
+    fn test_route_to_message_sets_tos() {
+        let route = route_with_tos(0x10);
+        let message = route_to_message(&route).unwrap();
+        assert_eq!(message.header.tos, 0x10);
+    }
+}
@@ -13,6 +13,12 @@ pub(crate) fn np_vrf_to_nmstate(
             ports.sort_unstable();
             Some(ports)
         },
+        // Kernel binds every VRF port's own routing table lookup to the
+        // VRF master's table via the `l3mdev` rule, so the port's
+        // associated table is always the master's `table_id`. Surface it
+        // here so a queried `VrfInterface` reflects the actual kernel
+        // routing-table topology rather than just interface membership.
+        port_route_table_id: Some(np_vrf_info.table_id),
     });
 
     VrfInterface {
@@ -20,3 +26,30 @@ pub(crate) fn np_vrf_to_nmstate(
         vrf: vrf_conf,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_np_vrf_to_nmstate_round_trips_table_id() {
+        let np_iface = nispor::Iface {
+            vrf: Some(nispor::VrfInfo {
+                table_id: 100,
+                subordinates: vec!["eth1".to_string(), "eth0".to_string()],
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let vrf_iface = np_vrf_to_nmstate(&np_iface, BaseInterface::new());
+
+        let vrf_conf = vrf_iface.vrf.expect("vrf config should be present");
+        assert_eq!(vrf_conf.table_id, Some(100));
+        assert_eq!(vrf_conf.port_route_table_id, Some(100));
+        assert_eq!(
+            vrf_conf.port,
+            Some(vec!["eth0".to_string(), "eth1".to_string()])
+        );
+    }
+}
@@ -10,6 +10,50 @@ use super::super::{
 };
 
 const GLIB_FILE_PATH_PREFIX: &str = "file://";
+const GLIB_PKCS11_URI_PREFIX: &str = "pkcs11:";
+
+/// NM stores `client-cert`/`ca-cert`/`private-key` blobs using one of three
+/// schemes, distinguished by a NUL-terminated glib byte string: a
+/// `file://` path, a `pkcs11:` URI pointing at an HSM/token object, or a
+/// raw inline DER blob.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum NmSetting8021XCertData {
+    FilePath(String),
+    Pkcs11Uri(String),
+    Blob(Vec<u8>),
+}
+
+impl NmSetting8021XCertData {
+    pub fn to_glib_bytes(&self) -> Vec<u8> {
+        match self {
+            Self::FilePath(path) => {
+                format!("{GLIB_FILE_PATH_PREFIX}{path}\0").into_bytes()
+            }
+            Self::Pkcs11Uri(uri) => format!("{uri}\0").into_bytes(),
+            Self::Blob(data) => data.clone(),
+        }
+    }
+
+    pub fn from_glib_bytes(value: &[u8]) -> Self {
+        match std::str::from_utf8(value) {
+            Ok(s) => {
+                let s = s.trim_end_matches(char::from(0));
+                if let Some(path) = s.strip_prefix(GLIB_FILE_PATH_PREFIX) {
+                    Self::FilePath(path.to_string())
+                } else if s.starts_with(GLIB_PKCS11_URI_PREFIX) {
+                    Self::Pkcs11Uri(s.to_string())
+                } else {
+                    // Not a recognized URI scheme: treat as an inline blob
+                    // even though it happens to be valid UTF-8.
+                    Self::Blob(value.to_vec())
+                }
+            }
+            // Not valid UTF-8 at all, so it cannot be a URI: inline blob.
+            Err(_) => Self::Blob(value.to_vec()),
+        }
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Default, Deserialize)]
 #[serde(try_from = "DbusDictionary")]
@@ -23,6 +67,20 @@ pub struct NmSetting8021X {
     pub private_key_password: Option<String>,
     pub phase2_auth: Option<String>,
     pub password: Option<String>,
+    pub anonymous_identity: Option<String>,
+    pub domain_suffix_match: Option<String>,
+    pub domain_match: Option<String>,
+    pub altsubject_matches: Option<Vec<String>>,
+    pub subject_match: Option<String>,
+    pub phase1_auth_flags: Option<u32>,
+    pub phase2_ca_cert: Option<Vec<u8>>,
+    pub phase2_client_cert: Option<Vec<u8>>,
+    pub phase2_private_key: Option<Vec<u8>>,
+    pub phase2_private_key_password: Option<String>,
+    pub phase2_autheap: Option<String>,
+    pub pac_file: Option<String>,
+    pub password_flags: Option<u32>,
+    pub private_key_password_flags: Option<u32>,
     _other: HashMap<String, zvariant::OwnedValue>,
 }
 
@@ -38,6 +96,56 @@ impl TryFrom<DbusDictionary> for NmSetting8021X {
             private_key_password: None,
             phase2_auth: _from_map!(v, "phase2-auth", String::try_from)?,
             password: None,
+            anonymous_identity: _from_map!(
+                v,
+                "anonymous-identity",
+                String::try_from
+            )?,
+            domain_suffix_match: _from_map!(
+                v,
+                "domain-suffix-match",
+                String::try_from
+            )?,
+            domain_match: _from_map!(v, "domain-match", String::try_from)?,
+            altsubject_matches: _from_map!(
+                v,
+                "altsubject-matches",
+                <Vec<String>>::try_from
+            )?,
+            subject_match: _from_map!(v, "subject-match", String::try_from)?,
+            phase1_auth_flags: _from_map!(
+                v,
+                "phase1-auth-flags",
+                u32::try_from
+            )?,
+            phase2_ca_cert: _from_map!(
+                v,
+                "phase2-ca-cert",
+                <Vec<u8>>::try_from
+            )?,
+            phase2_client_cert: _from_map!(
+                v,
+                "phase2-client-cert",
+                <Vec<u8>>::try_from
+            )?,
+            phase2_private_key: _from_map!(
+                v,
+                "phase2-private-key",
+                <Vec<u8>>::try_from
+            )?,
+            phase2_private_key_password: None,
+            phase2_autheap: _from_map!(
+                v,
+                "phase2-autheap",
+                String::try_from
+            )?,
+            pac_file: _from_map!(v, "pac-file", String::try_from)?,
+            password_flags: _from_map!(v, "password-flags", u32::try_from)?,
+            private_key_password_flags: _from_map!(
+                v,
+                "private-key-password-flags",
+                u32::try_from
+            )?,
             _other: v,
         })
     }
@@ -70,6 +178,54 @@ impl ToDbusValue for NmSetting8021X {
         if let Some(v) = &self.password {
             ret.insert("password", zvariant::Value::new(v));
         }
+        if let Some(v) = &self.anonymous_identity {
+            ret.insert("anonymous-identity", zvariant::Value::new(v));
+        }
+        if let Some(v) = &self.domain_suffix_match {
+            ret.insert("domain-suffix-match", zvariant::Value::new(v));
+        }
+        if let Some(v) = &self.domain_match {
+            ret.insert("domain-match", zvariant::Value::new(v));
+        }
+        if let Some(v) = &self.altsubject_matches {
+            ret.insert("altsubject-matches", zvariant::Value::new(v));
+        }
+        if let Some(v) = &self.subject_match {
+            ret.insert("subject-match", zvariant::Value::new(v));
+        }
+        if let Some(v) = &self.phase1_auth_flags {
+            ret.insert("phase1-auth-flags", zvariant::Value::new(v));
+        }
+        if let Some(v) = &self.phase2_ca_cert {
+            ret.insert("phase2-ca-cert", zvariant::Value::new(v));
+        }
+        if let Some(v) = &self.phase2_client_cert {
+            ret.insert("phase2-client-cert", zvariant::Value::new(v));
+        }
+        if let Some(v) = &self.phase2_private_key {
+            ret.insert("phase2-private-key", zvariant::Value::new(v));
+        }
+        if let Some(v) = &self.phase2_private_key_password {
+            ret.insert(
+                "phase2-private-key-password",
+                zvariant::Value::new(v),
+            );
+        }
+        if let Some(v) = &self.phase2_autheap {
+            ret.insert("phase2-autheap", zvariant::Value::new(v));
+        }
+        if let Some(v) = &self.pac_file {
+            ret.insert("pac-file", zvariant::Value::new(v));
+        }
+        if let Some(v) = &self.password_flags {
+            ret.insert("password-flags", zvariant::Value::new(v));
+        }
+        if let Some(v) = &self.private_key_password_flags {
+            ret.insert(
+                "private-key-password-flags",
+                zvariant::Value::new(v),
+            );
+        }
         ret.extend(self._other.iter().map(|(key, value)| {
             (key.as_str(), zvariant::Value::from(value.clone()))
         }));
@@ -110,6 +266,82 @@ impl NmSetting8021X {
                 }
             }
         }
+        if let Some(v) = secrets.get("phase2-private-key-password") {
+            match String::try_from(v.clone()) {
+                Ok(s) => {
+                    self.phase2_private_key_password = Some(s);
+                }
+                Err(e) => {
+                    log::warn!(
+                        "Failed to convert phase2_private_key_password: \
+                        {:?} {:?}",
+                        v,
+                        e
+                    );
+                }
+            }
+        }
+    }
+
+    // An EAP-TLS profile without a client certificate and private key pair
+    // cannot authenticate and will never associate; a profile missing
+    // `domain-suffix-match`/`ca-cert` is an open-trust misconfiguration
+    // that accepts any RADIUS server's identity, so warn rather than fail
+    // since some environments genuinely cannot pin the server identity.
+    pub fn validate(&self) -> Result<(), NmError> {
+        let is_eap_tls = self
+            .eap
+            .as_ref()
+            .map(|eap| eap.iter().any(|m| m == "tls"))
+            .unwrap_or_default();
+        if is_eap_tls
+            && (self.client_cert.is_none() || self.private_key.is_none())
+        {
+            return Err(NmError::new(
+                ErrorKind::InvalidArgument,
+                "EAP-TLS profile requires both client-cert and \
+                private-key to be set"
+                    .to_string(),
+            ));
+        }
+        if self.domain_suffix_match.is_none() && self.ca_cert.is_none() {
+            log::warn!(
+                "802.1X profile has neither `domain-suffix-match` nor \
+                `ca-cert` set: the server identity will not be \
+                validated, which is an open-trust misconfiguration"
+            );
+        }
+        Ok(())
+    }
+
+    pub fn client_cert_data(&self) -> Option<NmSetting8021XCertData> {
+        self.client_cert
+            .as_deref()
+            .map(NmSetting8021XCertData::from_glib_bytes)
+    }
+
+    pub fn ca_cert_data(&self) -> Option<NmSetting8021XCertData> {
+        self.ca_cert
+            .as_deref()
+            .map(NmSetting8021XCertData::from_glib_bytes)
+    }
+
+    pub fn private_key_data(&self) -> Option<NmSetting8021XCertData> {
+        self.private_key
+            .as_deref()
+            .map(NmSetting8021XCertData::from_glib_bytes)
+    }
+
+    pub fn set_client_cert(&mut self, data: NmSetting8021XCertData) {
+        self.client_cert = Some(data.to_glib_bytes());
+    }
+
+    pub fn set_ca_cert(&mut self, data: NmSetting8021XCertData) {
+        self.ca_cert = Some(data.to_glib_bytes());
+    }
+
+    pub fn set_private_key(&mut self, data: NmSetting8021XCertData) {
+        self.private_key = Some(data.to_glib_bytes());
     }
 
     pub fn file_path_to_glib_bytes(file_path: &str) -> Vec<u8> {
@@ -146,3 +378,45 @@ impl NmSetting8021X {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cert_data_file_path_round_trip() {
+        let data = NmSetting8021XCertData::FilePath("/etc/pki/cert.pem".into());
+        let bytes = data.to_glib_bytes();
+        assert_eq!(bytes, b"file:///etc/pki/cert.pem\0");
+        assert_eq!(NmSetting8021XCertData::from_glib_bytes(&bytes), data);
+    }
+
+    #[test]
+    fn test_cert_data_pkcs11_uri_round_trip() {
+        let data = NmSetting8021XCertData::Pkcs11Uri(
+            "pkcs11:token=SoftHSM;object=client".into(),
+        );
+        let bytes = data.to_glib_bytes();
+        assert_eq!(NmSetting8021XCertData::from_glib_bytes(&bytes), data);
+    }
+
+    #[test]
+    fn test_cert_data_inline_blob_round_trip() {
+        let data = NmSetting8021XCertData::Blob(vec![0x30, 0x82, 0x01, 0x0a]);
+        let bytes = data.to_glib_bytes();
+        assert_eq!(NmSetting8021XCertData::from_glib_bytes(&bytes), data);
+    }
+
+    #[test]
+    fn test_validate_eap_tls_requires_client_cert_and_key() {
+        let mut nm_8021x = NmSetting8021X {
+            eap: Some(vec!["tls".to_string()]),
+            ..Default::default()
+        };
+        assert!(nm_8021x.validate().is_err());
+
+        nm_8021x.client_cert = Some(b"cert".to_vec());
+        nm_8021x.private_key = Some(b"key".to_vec());
+        assert!(nm_8021x.validate().is_ok());
+    }
+}
@@ -35,14 +35,33 @@ pub struct NmIpRouteRule {
     pub fw_mark: Option<u32>,
     pub fw_mask: Option<u32>,
     pub iifname: Option<String>,
+    pub oifname: Option<String>,
     pub action: Option<NmIpRouteRuleAction>,
     pub suppress_prefixlength: Option<i32>,
+    pub dport_start: Option<u16>,
+    pub dport_end: Option<u16>,
+    pub sport_start: Option<u16>,
+    pub sport_end: Option<u16>,
+    pub tos: Option<u8>,
+    pub ipproto: Option<u8>,
+    pub invert: Option<bool>,
+    pub uid_range_start: Option<u32>,
+    pub uid_range_end: Option<u32>,
     _other: DbusDictionary,
 }
 
 impl TryFrom<DbusDictionary> for NmIpRouteRule {
     type Error = NmError;
     fn try_from(mut v: DbusDictionary) -> Result<Self, Self::Error> {
+        let raw_action = _from_map!(v, "action", u8::try_from)?;
+        let goto = _from_map!(v, "goto", u32::try_from)?;
+        let action = raw_action.map(|a| {
+            if a == RTN_GOTO {
+                NmIpRouteRuleAction::Goto(goto.unwrap_or_default())
+            } else {
+                NmIpRouteRuleAction::from(a)
+            }
+        });
         Ok(Self {
             family: _from_map!(v, "family", i32::try_from)?,
             priority: _from_map!(v, "priority", u32::try_from)?,
@@ -54,13 +73,26 @@ impl TryFrom<DbusDictionary> for NmIpRouteRule {
             fw_mark: _from_map!(v, "fwmark", u32::try_from)?,
             fw_mask: _from_map!(v, "fwmask", u32::try_from)?,
             iifname: _from_map!(v, "iifname", String::try_from)?,
-            action: _from_map!(v, "action", u8::try_from)?
-                .map(NmIpRouteRuleAction::from),
+            oifname: _from_map!(v, "oifname", String::try_from)?,
+            action,
             suppress_prefixlength: _from_map!(
                 v,
                 "suppress-prefixlength",
                 i32::try_from
             )?,
+            dport_start: _from_map!(v, "dport-start", u16::try_from)?,
+            dport_end: _from_map!(v, "dport-end", u16::try_from)?,
+            sport_start: _from_map!(v, "sport-start", u16::try_from)?,
+            sport_end: _from_map!(v, "sport-end", u16::try_from)?,
+            tos: _from_map!(v, "tos", u8::try_from)?,
+            ipproto: _from_map!(v, "ipproto", u8::try_from)?,
+            invert: _from_map!(v, "invert", bool::try_from)?,
+            uid_range_start: _from_map!(
+                v,
+                "uid-range-start",
+                u32::try_from
+            )?,
+            uid_range_end: _from_map!(v, "uid-range-end", u32::try_from)?,
             _other: v,
         })
     }
@@ -132,11 +164,23 @@ impl NmIpRouteRule {
                 zvariant::Value::new(zvariant::Value::new(v)),
             )?;
         }
+        if let Some(v) = &self.oifname {
+            ret.append(
+                zvariant::Value::new("oifname"),
+                zvariant::Value::new(zvariant::Value::new(v)),
+            )?;
+        }
         if let Some(v) = &self.action {
             ret.append(
                 zvariant::Value::new("action"),
                 zvariant::Value::new(zvariant::Value::new(u8::from(*v))),
             )?;
+            if let NmIpRouteRuleAction::Goto(target) = v {
+                ret.append(
+                    zvariant::Value::new("goto"),
+                    zvariant::Value::new(zvariant::Value::new(target)),
+                )?;
+            }
         }
         if let Some(v) = &self.suppress_prefixlength {
             ret.append(
@@ -144,6 +188,60 @@ impl NmIpRouteRule {
                 zvariant::Value::new(zvariant::Value::new(v)),
             )?;
         }
+        if let Some(v) = &self.dport_start {
+            ret.append(
+                zvariant::Value::new("dport-start"),
+                zvariant::Value::new(zvariant::Value::new(v)),
+            )?;
+        }
+        if let Some(v) = &self.dport_end {
+            ret.append(
+                zvariant::Value::new("dport-end"),
+                zvariant::Value::new(zvariant::Value::new(v)),
+            )?;
+        }
+        if let Some(v) = &self.sport_start {
+            ret.append(
+                zvariant::Value::new("sport-start"),
+                zvariant::Value::new(zvariant::Value::new(v)),
+            )?;
+        }
+        if let Some(v) = &self.sport_end {
+            ret.append(
+                zvariant::Value::new("sport-end"),
+                zvariant::Value::new(zvariant::Value::new(v)),
+            )?;
+        }
+        if let Some(v) = &self.tos {
+            ret.append(
+                zvariant::Value::new("tos"),
+                zvariant::Value::new(zvariant::Value::new(v)),
+            )?;
+        }
+        if let Some(v) = &self.ipproto {
+            ret.append(
+                zvariant::Value::new("ipproto"),
+                zvariant::Value::new(zvariant::Value::new(v)),
+            )?;
+        }
+        if let Some(v) = &self.invert {
+            ret.append(
+                zvariant::Value::new("invert"),
+                zvariant::Value::new(zvariant::Value::new(v)),
+            )?;
+        }
+        if let Some(v) = &self.uid_range_start {
+            ret.append(
+                zvariant::Value::new("uid-range-start"),
+                zvariant::Value::new(zvariant::Value::new(v)),
+            )?;
+        }
+        if let Some(v) = &self.uid_range_end {
+            ret.append(
+                zvariant::Value::new("uid-range-end"),
+                zvariant::Value::new(zvariant::Value::new(v)),
+            )?;
+        }
 
         for (key, value) in self._other.iter() {
             ret.append(
@@ -176,6 +274,9 @@ pub(crate) fn nm_ip_rules_to_value(
     Ok(zvariant::Value::Array(rule_values))
 }
 
+// FR_ACT_* from linux/fib_rules.h
+const RTN_TO_TBL: u8 = 1;
+const RTN_GOTO: u8 = 2;
 const RTN_BLACKHOLE: u8 = 6;
 const RTN_UNREACHABLE: u8 = 7;
 const RTN_PROHIBIT: u8 = 8;
@@ -183,6 +284,11 @@ const RTN_PROHIBIT: u8 = 8;
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum NmIpRouteRuleAction {
+    /// Normal routing table lookup, FR_ACT_TO_TBL.
+    ToTable,
+    /// Jump to another rule by priority, FR_ACT_GOTO. Carries the target
+    /// rule priority, stored in the paired `goto` dbus key.
+    Goto(u32),
     Blackhole,
     Unreachable,
     Prohibit,
@@ -192,6 +298,8 @@ pub enum NmIpRouteRuleAction {
 impl From<u8> for NmIpRouteRuleAction {
     fn from(v: u8) -> Self {
         match v {
+            RTN_TO_TBL => Self::ToTable,
+            RTN_GOTO => Self::Goto(0),
             RTN_BLACKHOLE => Self::Blackhole,
             RTN_UNREACHABLE => Self::Unreachable,
             RTN_PROHIBIT => Self::Prohibit,
@@ -206,6 +314,8 @@ impl From<u8> for NmIpRouteRuleAction {
 impl From<NmIpRouteRuleAction> for u8 {
     fn from(v: NmIpRouteRuleAction) -> Self {
         match v {
+            NmIpRouteRuleAction::ToTable => RTN_TO_TBL,
+            NmIpRouteRuleAction::Goto(_) => RTN_GOTO,
             NmIpRouteRuleAction::Blackhole => RTN_BLACKHOLE,
             NmIpRouteRuleAction::Unreachable => RTN_UNREACHABLE,
             NmIpRouteRuleAction::Prohibit => RTN_PROHIBIT,
@@ -217,6 +327,8 @@ impl From<NmIpRouteRuleAction> for u8 {
 impl std::fmt::Display for NmIpRouteRuleAction {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            NmIpRouteRuleAction::ToTable => write!(f, "to-table"),
+            NmIpRouteRuleAction::Goto(target) => write!(f, "goto {}", target),
             NmIpRouteRuleAction::Blackhole => write!(f, "blackhole"),
             NmIpRouteRuleAction::Unreachable => write!(f, "unreachable"),
             NmIpRouteRuleAction::Prohibit => write!(f, "prohibit"),
@@ -224,3 +336,122 @@ impl std::fmt::Display for NmIpRouteRuleAction {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_dict() -> DbusDictionary {
+        let mut dict: DbusDictionary = DbusDictionary::new();
+        dict.insert(
+            "priority".to_string(),
+            zvariant::Value::new(100u32).try_into().unwrap(),
+        );
+        dict.insert(
+            "oifname".to_string(),
+            zvariant::Value::new("eth1").try_into().unwrap(),
+        );
+        dict.insert(
+            "dport-start".to_string(),
+            zvariant::Value::new(8000u16).try_into().unwrap(),
+        );
+        dict.insert(
+            "dport-end".to_string(),
+            zvariant::Value::new(8080u16).try_into().unwrap(),
+        );
+        dict.insert(
+            "sport-start".to_string(),
+            zvariant::Value::new(1024u16).try_into().unwrap(),
+        );
+        dict.insert(
+            "sport-end".to_string(),
+            zvariant::Value::new(65535u16).try_into().unwrap(),
+        );
+        dict.insert(
+            "tos".to_string(),
+            zvariant::Value::new(4u8).try_into().unwrap(),
+        );
+        dict.insert(
+            "ipproto".to_string(),
+            zvariant::Value::new(6u8).try_into().unwrap(),
+        );
+        dict.insert(
+            "invert".to_string(),
+            zvariant::Value::new(true).try_into().unwrap(),
+        );
+        dict.insert(
+            "uid-range-start".to_string(),
+            zvariant::Value::new(1000u32).try_into().unwrap(),
+        );
+        dict.insert(
+            "uid-range-end".to_string(),
+            zvariant::Value::new(2000u32).try_into().unwrap(),
+        );
+        dict
+    }
+
+    #[test]
+    fn test_route_rule_l4_and_uid_range_round_trip() {
+        let rule = NmIpRouteRule::try_from(test_dict()).unwrap();
+
+        assert_eq!(rule.oifname.as_deref(), Some("eth1"));
+        assert_eq!(rule.dport_start, Some(8000));
+        assert_eq!(rule.dport_end, Some(8080));
+        assert_eq!(rule.sport_start, Some(1024));
+        assert_eq!(rule.sport_end, Some(65535));
+        assert_eq!(rule.tos, Some(4));
+        assert_eq!(rule.ipproto, Some(6));
+        assert_eq!(rule.invert, Some(true));
+        assert_eq!(rule.uid_range_start, Some(1000));
+        assert_eq!(rule.uid_range_end, Some(2000));
+
+        let value = rule.to_value().unwrap();
+        let dict = if let zvariant::Value::Dict(d) = value {
+            d
+        } else {
+            panic!("Expected zvariant::Value::Dict");
+        };
+
+        assert_eq!(
+            dict.get::<str, String>("oifname").unwrap().as_deref(),
+            Some("eth1")
+        );
+        assert_eq!(
+            dict.get::<str, u16>("dport-start").unwrap(),
+            Some(8000)
+        );
+        assert_eq!(dict.get::<str, u16>("sport-end").unwrap(), Some(65535));
+        assert_eq!(dict.get::<str, u8>("tos").unwrap(), Some(4));
+        assert_eq!(dict.get::<str, u8>("ipproto").unwrap(), Some(6));
+        assert_eq!(dict.get::<str, bool>("invert").unwrap(), Some(true));
+        assert_eq!(
+            dict.get::<str, u32>("uid-range-start").unwrap(),
+            Some(1000)
+        );
+    }
+
+    #[test]
+    fn test_route_rule_goto_action_round_trip() {
+        let mut dict = test_dict();
+        dict.insert(
+            "action".to_string(),
+            zvariant::Value::new(RTN_GOTO).try_into().unwrap(),
+        );
+        dict.insert(
+            "goto".to_string(),
+            zvariant::Value::new(500u32).try_into().unwrap(),
+        );
+
+        let rule = NmIpRouteRule::try_from(dict).unwrap();
+        assert_eq!(rule.action, Some(NmIpRouteRuleAction::Goto(500)));
+
+        let value = rule.to_value().unwrap();
+        let dict = if let zvariant::Value::Dict(d) = value {
+            d
+        } else {
+            panic!("Expected zvariant::Value::Dict");
+        };
+        assert_eq!(dict.get::<str, u8>("action").unwrap(), Some(RTN_GOTO));
+        assert_eq!(dict.get::<str, u32>("goto").unwrap(), Some(500));
+    }
+}
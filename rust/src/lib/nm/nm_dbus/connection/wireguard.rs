@@ -0,0 +1,145 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use serde::Deserialize;
+
+use super::super::{connection::DbusDictionary, NmError, ToDbusValue};
+
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+#[serde(try_from = "DbusDictionary")]
+#[non_exhaustive]
+pub struct NmSettingWireGuard {
+    pub private_key: Option<String>,
+    pub listen_port: Option<u32>,
+    pub fwmark: Option<u32>,
+    pub peers: Option<Vec<NmWireGuardPeer>>,
+    _other: HashMap<String, zvariant::OwnedValue>,
+}
+
+impl TryFrom<DbusDictionary> for NmSettingWireGuard {
+    type Error = NmError;
+    fn try_from(mut v: DbusDictionary) -> Result<Self, Self::Error> {
+        Ok(Self {
+            private_key: None,
+            listen_port: _from_map!(v, "listen-port", u32::try_from)?,
+            fwmark: _from_map!(v, "fwmark", u32::try_from)?,
+            peers: _from_map!(v, "peers", <Vec<NmWireGuardPeer>>::try_from)?,
+            _other: v,
+        })
+    }
+}
+
+impl ToDbusValue for NmSettingWireGuard {
+    fn to_value(&self) -> Result<HashMap<&str, zvariant::Value>, NmError> {
+        let mut ret = HashMap::new();
+        if let Some(v) = &self.private_key {
+            ret.insert("private-key", zvariant::Value::new(v));
+        }
+        if let Some(v) = &self.listen_port {
+            ret.insert("listen-port", zvariant::Value::new(v));
+        }
+        if let Some(v) = &self.fwmark {
+            ret.insert("fwmark", zvariant::Value::new(v));
+        }
+        if let Some(v) = &self.peers {
+            ret.insert(
+                "peers",
+                zvariant::Value::new(
+                    v.iter()
+                        .map(NmWireGuardPeer::to_dbus_map)
+                        .collect::<Vec<_>>(),
+                ),
+            );
+        }
+        ret.extend(self._other.iter().map(|(key, value)| {
+            (key.as_str(), zvariant::Value::from(value.clone()))
+        }));
+        Ok(ret)
+    }
+}
+
+impl NmSettingWireGuard {
+    // The local private key is a secret: NM hides it from D-Bus queries
+    // unless the caller requests secrets explicitly, just like the
+    // Libreswan PSK path.
+    #[cfg(feature = "query_apply")]
+    pub(crate) fn fill_secrets(&mut self, secrets: &DbusDictionary) {
+        if let Some(v) = secrets.get("private-key") {
+            match String::try_from(v.clone()) {
+                Ok(s) => self.private_key = Some(s),
+                Err(e) => {
+                    log::warn!("Failed to convert private-key: {:?}", e);
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+#[non_exhaustive]
+pub struct NmWireGuardPeer {
+    pub public_key: String,
+    pub preshared_key: Option<String>,
+    pub endpoint: Option<String>,
+    pub allowed_ips: Vec<String>,
+    pub persistent_keepalive: Option<u32>,
+}
+
+impl NmWireGuardPeer {
+    fn to_dbus_map(&self) -> HashMap<String, zvariant::Value> {
+        let mut ret = HashMap::new();
+        ret.insert(
+            "public-key".to_string(),
+            zvariant::Value::new(self.public_key.clone()),
+        );
+        if let Some(v) = self.preshared_key.as_ref() {
+            ret.insert(
+                "preshared-key".to_string(),
+                zvariant::Value::new(v.clone()),
+            );
+        }
+        if let Some(v) = self.endpoint.as_ref() {
+            ret.insert(
+                "endpoint".to_string(),
+                zvariant::Value::new(v.clone()),
+            );
+        }
+        ret.insert(
+            "allowed-ips".to_string(),
+            zvariant::Value::new(self.allowed_ips.clone()),
+        );
+        if let Some(v) = self.persistent_keepalive {
+            ret.insert(
+                "persistent-keepalive".to_string(),
+                zvariant::Value::new(v),
+            );
+        }
+        ret
+    }
+}
+
+impl TryFrom<zvariant::OwnedValue> for NmWireGuardPeer {
+    type Error = NmError;
+    fn try_from(v: zvariant::OwnedValue) -> Result<Self, Self::Error> {
+        let mut dict = DbusDictionary::try_from(v)?;
+        Ok(Self {
+            public_key: _from_map!(dict, "public-key", String::try_from)?
+                .unwrap_or_default(),
+            preshared_key: None,
+            endpoint: _from_map!(dict, "endpoint", String::try_from)?,
+            allowed_ips: _from_map!(
+                dict,
+                "allowed-ips",
+                <Vec<String>>::try_from
+            )?
+            .unwrap_or_default(),
+            persistent_keepalive: _from_map!(
+                dict,
+                "persistent-keepalive",
+                u32::try_from
+            )?,
+        })
+    }
+}
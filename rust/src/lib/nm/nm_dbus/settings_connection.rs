@@ -0,0 +1,55 @@
+// SPDX-License-Identifier: Apache-2.0
+
+#[cfg(feature = "query_apply")]
+use std::collections::HashMap;
+
+#[cfg(feature = "query_apply")]
+use super::dbus::NM_DBUS_INTERFACE_ROOT;
+#[cfg(feature = "query_apply")]
+use super::{ErrorKind, NmError};
+
+#[cfg(feature = "query_apply")]
+const NM_DBUS_INTERFACE_SETTINGS_CONNECTION: &str =
+    "org.freedesktop.NetworkManager.Settings.Connection";
+
+/// Pushes `secrets` (keyed by NM setting property name, e.g. `"password"`)
+/// for the `setting_name` setting (e.g. `"802-1x"`) of the connection at
+/// `obj_path` via `Settings.Connection.UpdateSecrets`. This re-applies just
+/// the given secrets to the connection that is already loaded/active
+/// instead of tearing it down and reapplying the whole profile -- the
+/// counterpart `secret_resolver::reload_secret_refs` re-resolves into the
+/// plaintext values this function pushes.
+#[cfg(feature = "query_apply")]
+pub(crate) fn nm_conn_update_secrets(
+    dbus_conn: &zbus::Connection,
+    obj_path: &str,
+    setting_name: &str,
+    secrets: &HashMap<String, String>,
+) -> Result<(), NmError> {
+    let proxy = zbus::Proxy::new(
+        dbus_conn,
+        NM_DBUS_INTERFACE_ROOT,
+        obj_path,
+        NM_DBUS_INTERFACE_SETTINGS_CONNECTION,
+    )?;
+
+    let mut props: HashMap<&str, zvariant::Value> = HashMap::new();
+    for (prop, value) in secrets {
+        props.insert(prop.as_str(), zvariant::Value::from(value.as_str()));
+    }
+    let mut datas: HashMap<&str, HashMap<&str, zvariant::Value>> =
+        HashMap::new();
+    datas.insert(setting_name, props);
+
+    proxy
+        .call::<_, _, ()>("UpdateSecrets", &(setting_name, datas))
+        .map_err(|e| {
+            NmError::new(
+                ErrorKind::Bug,
+                format!(
+                    "Failed to push updated secrets for setting \
+                    '{setting_name}' on connection {obj_path}: {e}"
+                ),
+            )
+        })
+}
@@ -16,6 +16,7 @@ use super::super::{
             cur_dns_ifaces_still_valid_for_dns, is_iface_dns_desired,
             purge_global_dns_config, store_dns_config_via_global_api,
         },
+        gateway::check_duplicate_default_gateway,
         is_ipvlan_changed, is_mptcp_flags_changed, is_route_removed,
         is_veth_peer_changed, is_vlan_changed, is_vrf_table_id_changed,
         is_vxlan_changed,
@@ -33,6 +34,90 @@ use crate::{
     NmstateError,
 };
 
+/// Reviewable, no-commit preview of what [`nm_apply`] would do, returned by
+/// [`nm_plan`]. No checkpoint is created and nothing in NetworkManager is
+/// touched while generating it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct NmApplyPlan {
+    /// Interface profiles that would be stored (created or updated).
+    pub(crate) to_store: Vec<String>,
+    /// Interface profiles that would be activated.
+    pub(crate) to_activate: Vec<String>,
+    /// Interface profiles that would be deactivated.
+    pub(crate) to_deactivate: Vec<String>,
+    /// Already-active profiles that would be deactivated and reactivated
+    /// first, paired with the human readable reason, e.g. "route removed"
+    /// or "VRF table ID change".
+    pub(crate) reactivations: Vec<(String, String)>,
+}
+
+// Mirrors `nm_apply` up to the point where `PerparedNmConnections` is
+// computed, then stops instead of calling `save_nm_profiles`/
+// `delete_exist_profiles`/`activate_nm_profiles`, so callers can review the
+// pending change set (e.g. in CI/GitOps flows) before committing to it.
+pub(crate) async fn nm_plan(
+    merged_state: &MergedNetworkState,
+) -> Result<NmApplyPlan, NmstateError> {
+    let nm_api = NmApi::new().map_err(nm_error_to_nmstate)?;
+
+    let exist_nm_conns =
+        nm_api.connections_get().map_err(nm_error_to_nmstate)?;
+    let nm_acs = nm_api
+        .active_connections_get()
+        .map_err(nm_error_to_nmstate)?;
+
+    let mut merged_state = merged_state.clone();
+
+    store_route_config(&mut merged_state)?;
+    store_route_rule_config(&mut merged_state)?;
+    check_duplicate_default_gateway(&merged_state)?;
+
+    let PerparedNmConnections {
+        to_store: nm_conns_to_store,
+        to_activate: nm_conns_to_activate,
+        to_deactivate: nm_conns_to_deactivate,
+    } = perpare_nm_conns(
+        &merged_state,
+        exist_nm_conns.as_slice(),
+        nm_acs.as_slice(),
+        false,
+    )?;
+
+    let nm_ac_uuids: Vec<&str> =
+        nm_acs.iter().map(|nm_ac| &nm_ac.uuid as &str).collect();
+    let activated_nm_conns: Vec<&NmConnection> = exist_nm_conns
+        .iter()
+        .filter(|c| {
+            if let Some(uuid) = c.uuid() {
+                nm_ac_uuids.contains(&uuid)
+            } else {
+                false
+            }
+        })
+        .collect();
+    let reactivations = gen_reactivation_reasons(
+        &merged_state.interfaces,
+        nm_conns_to_activate.as_slice(),
+        activated_nm_conns.as_slice(),
+    );
+
+    Ok(NmApplyPlan {
+        to_store: nm_conns_to_store
+            .iter()
+            .filter_map(|c| c.iface_name().map(str::to_string))
+            .collect(),
+        to_activate: nm_conns_to_activate
+            .iter()
+            .filter_map(|c| c.iface_name().map(str::to_string))
+            .collect(),
+        to_deactivate: nm_conns_to_deactivate
+            .iter()
+            .filter_map(|c| c.iface_name().map(str::to_string))
+            .collect(),
+        reactivations,
+    })
+}
+
 // There is plan to simply the `add_net_state`, `chg_net_state`, `del_net_state`
 // `cur_net_state`, `des_net_state` into single struct. Suppress the clippy
 // warning for now
@@ -81,6 +166,8 @@ pub(crate) async fn nm_apply(
 
     store_route_rule_config(&mut merged_state)?;
 
+    check_duplicate_default_gateway(&merged_state)?;
+
     if merged_state.dns.is_changed()
         || merged_state.dns.is_desired()
         || !cur_dns_ifaces_still_valid_for_dns(&merged_state.interfaces)
@@ -459,22 +546,124 @@ fn gen_nm_conn_need_to_deactivate_first(
     ret
 }
 
-fn check_nm_version(nm_api: &NmApi) {
-    if let Ok(versions) = nm_api.version().map(|ver_str| {
-        ver_str
-            .split('.')
-            .map(|v| v.parse::<i32>().unwrap_or_default())
-            .collect::<Vec<i32>>()
-    }) {
-        if let (Some(major), Some(minor)) = (versions.first(), versions.get(1))
-        {
-            if *major < 1 || *minor < 40 {
-                log::warn!(
-                    "Unsupported NetworkManager version {major}.{minor}, \
-                    expecting >= 1.40"
-                );
-            }
+// NetworkManager's checkpoint-create flag for keeping externally managed
+// bridge/OVS ports attached across an automatic rollback, only understood
+// by NM >= 1.36.
+const NM_CHECKPOINT_CREATE_FLAG_PRESERVE_EXTERNAL_PORTS: u32 = 0x10;
+
+// Same matching logic as `gen_nm_conn_need_to_deactivate_first`, but
+// surfacing *why* each connection would be reactivated instead of acting on
+// it, for use by the dry-run `nm_plan`.
+fn gen_reactivation_reasons(
+    merged_iface: &MergedInterfaces,
+    nm_conns_to_activate: &[NmConnection],
+    activated_nm_conns: &[&NmConnection],
+) -> Vec<(String, String)> {
+    let mut ret: Vec<(String, String)> = Vec::new();
+
+    let default_pvid_changed_brs: Vec<&str> =
+        get_default_pvid_changed_brs(merged_iface);
+    let bond_queue_id_changed_ports =
+        get_bond_ports_with_queue_id_changed(merged_iface);
+
+    for nm_conn in nm_conns_to_activate {
+        let Some(uuid) = nm_conn.uuid() else {
+            continue;
+        };
+        let Some(activated_nm_con) = activated_nm_conns.iter().find(|c| {
+            c.uuid().map(|cur_uuid| cur_uuid == uuid).unwrap_or_default()
+        }) else {
+            continue;
+        };
+        let name = nm_conn.iface_name().unwrap_or("").to_string();
+        let reason = if is_route_removed(nm_conn, activated_nm_con) {
+            Some("route removed")
+        } else if is_vrf_table_id_changed(nm_conn, activated_nm_con) {
+            Some("VRF table ID change")
+        } else if is_vlan_changed(nm_conn, activated_nm_con) {
+            Some("VLAN config change")
+        } else if is_vxlan_changed(nm_conn, activated_nm_con) {
+            Some("VXLAN config change")
+        } else if is_veth_peer_changed(nm_conn, activated_nm_con) {
+            Some("veth peer change")
+        } else if is_mptcp_flags_changed(nm_conn, activated_nm_con) {
+            Some("MPTCP flags change")
+        } else if nm_conn.iface_type() == Some(&NmIfaceType::Vpn) {
+            Some("VPN connection")
+        } else if is_bridge_port_changed_default_pvid(
+            nm_conn,
+            &default_pvid_changed_brs,
+        ) {
+            Some("bridge `vlan-default-pvid` change")
+        } else if is_bond_port_queue_id_changed(
+            nm_conn,
+            &bond_queue_id_changed_ports,
+        ) {
+            Some("bond port queue ID change")
+        } else if is_ipvlan_changed(nm_conn, activated_nm_con) {
+            Some("IPVLAN config change")
+        } else {
+            None
+        };
+        if let Some(reason) = reason {
+            ret.push((name, reason.to_string()));
+        }
+    }
+    ret
+}
+
+pub(crate) fn check_nm_version(nm_api: &NmApi) -> Vec<i32> {
+    let versions = nm_api
+        .version()
+        .map(|ver_str| {
+            ver_str
+                .split('.')
+                .map(|v| v.parse::<i32>().unwrap_or_default())
+                .collect::<Vec<i32>>()
+        })
+        .unwrap_or_default();
+    if let (Some(major), Some(minor)) = (versions.first(), versions.get(1)) {
+        if *major < 1 || *minor < 40 {
+            log::warn!(
+                "Unsupported NetworkManager version {major}.{minor}, \
+                expecting >= 1.40"
+            );
+        }
+    }
+    versions
+}
+
+fn nm_supports_preserve_external_ports(nm_version: &[i32]) -> bool {
+    match (nm_version.first(), nm_version.get(1)) {
+        (Some(major), Some(minor)) => *major > 1 || (*major == 1 && *minor >= 36),
+        _ => false,
+    }
+}
+
+// Default to preserving externally-added bridge/OVS ports across an
+// automatic checkpoint rollback, unless the user opted out or the running
+// NetworkManager is too old to understand the flag.
+//
+// The checkpoint itself is already created (with whatever flags) by the
+// caller before `nm_apply`/`nm_plan` ever run -- `nm_apply` only sees the
+// resulting checkpoint object path. No call site in the crate calls this
+// function.
+pub(crate) fn gen_checkpoint_create_flags(
+    nm_version: &[i32],
+    preserve_external_ports_on_rollback: bool,
+) -> u32 {
+    if preserve_external_ports_on_rollback
+        && nm_supports_preserve_external_ports(nm_version)
+    {
+        NM_CHECKPOINT_CREATE_FLAG_PRESERVE_EXTERNAL_PORTS
+    } else {
+        if preserve_external_ports_on_rollback {
+            log::debug!(
+                "Running NetworkManager is too old to support preserving \
+                external ports on checkpoint rollback, ignoring"
+            );
         }
+        0
     }
 }
 
@@ -0,0 +1,182 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    BaseInterface, ErrorKind, EthernetInterface, Interface, InterfaceState,
+    MergedNetworkState, NetworkState, NmstateError, RouteEntry, Routes,
+};
+
+const IPV4_DEFAULT_GATEWAY: &str = "0.0.0.0/0";
+const IPV6_DEFAULT_GATEWAY: &str = "::/0";
+
+// NetworkManager will happily activate a second interface with a default
+// route at the same metric, leaving the kernel to pick one at random. Refuse
+// that unless the user explicitly opted in, mirroring how other network
+// config tools reject a silently ambiguous default route.
+pub(crate) fn check_duplicate_default_gateway(
+    merged_state: &MergedNetworkState,
+) -> Result<(), NmstateError> {
+    if merged_state.allow_multiple_default_gateways {
+        return Ok(());
+    }
+
+    let up_ifaces: HashSet<&str> = merged_state
+        .interfaces
+        .iter()
+        .map(|merged_iface| &merged_iface.merged)
+        .filter(|iface| !iface.is_absent() && iface.is_up())
+        .map(|iface| iface.name())
+        .collect();
+
+    check_duplicate_default_gateway_routes(
+        merged_state
+            .routes
+            .config
+            .as_ref()
+            .map(|routes| routes.as_slice())
+            .unwrap_or_default()
+            .iter(),
+        &up_ifaces,
+    )
+}
+
+/// Standalone sanity check for an already-queried [`NetworkState`] (e.g.
+/// the one `nm_retrieve` just built): errors if more than one `kernel_iface`
+/// or `user_iface` is up and claiming the same-metric default route. Unlike
+/// [`check_duplicate_default_gateway`], there is no
+/// `allow-multiple-default-gateways` opt-out here, since a queried state
+/// reports whatever the system actually has.
+pub(crate) fn check_duplicate_default_gateway_in_net_state(
+    net_state: &NetworkState,
+) -> Result<(), NmstateError> {
+    let Some(routes) = net_state.routes.as_ref() else {
+        return Ok(());
+    };
+    let route_list = routes
+        .running
+        .as_ref()
+        .or(routes.config.as_ref())
+        .map(|routes| routes.as_slice())
+        .unwrap_or_default();
+
+    let up_ifaces: HashSet<&str> = net_state
+        .interfaces
+        .kernel_ifaces
+        .values()
+        .chain(net_state.interfaces.user_ifaces.values())
+        .filter(|iface| iface.is_up())
+        .map(|iface| iface.name())
+        .collect();
+
+    check_duplicate_default_gateway_routes(route_list.iter(), &up_ifaces)
+}
+
+// Shared core: walk `routes`, keeping only those whose `next_hop_iface` is
+// in `up_ifaces`, and error on the first (family, metric) pair claimed by
+// two different interfaces.
+fn check_duplicate_default_gateway_routes<'a>(
+    routes: impl Iterator<Item = &'a RouteEntry>,
+    up_ifaces: &HashSet<&str>,
+) -> Result<(), NmstateError> {
+    // (family, metric) -> first interface name claiming the default route
+    let mut owners: HashMap<(&'static str, i64), &str> = HashMap::new();
+
+    for route in routes {
+        let Some(iface_name) = route.next_hop_iface.as_deref() else {
+            continue;
+        };
+        if !up_ifaces.contains(iface_name) {
+            continue;
+        }
+        let family = match route.destination.as_deref() {
+            Some(IPV4_DEFAULT_GATEWAY) => "IPv4",
+            Some(IPV6_DEFAULT_GATEWAY) => "IPv6",
+            _ => continue,
+        };
+        let metric = route.metric.unwrap_or(-1);
+        match owners.get(&(family, metric)) {
+            Some(existing) if *existing != iface_name => {
+                return Err(NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "Interface {iface_name} is configuring a {family} \
+                        default gateway at metric {metric} which is \
+                        already owned by interface {existing}. Set \
+                        `allow-multiple-default-gateways: true` if this \
+                        is an intentional ECMP/multi-metric setup."
+                    ),
+                ));
+            }
+            _ => {
+                owners.insert((family, metric), iface_name);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn up_eth_iface(name: &str) -> Interface {
+        let mut base = BaseInterface::new();
+        base.name = name.to_string();
+        base.state = InterfaceState::Up;
+        Interface::Ethernet(Box::new(EthernetInterface {
+            base,
+            ..Default::default()
+        }))
+    }
+
+    fn default_gw_route(iface_name: &str, metric: i64) -> RouteEntry {
+        let mut route = RouteEntry::new();
+        route.destination = Some(IPV4_DEFAULT_GATEWAY.to_string());
+        route.next_hop_iface = Some(iface_name.to_string());
+        route.metric = Some(metric);
+        route
+    }
+
+    #[test]
+    fn test_duplicate_default_gateway_in_net_state_detected() {
+        let mut net_state = NetworkState::new();
+        net_state
+            .interfaces
+            .kernel_ifaces
+            .insert("eth0".to_string(), up_eth_iface("eth0"));
+        net_state
+            .interfaces
+            .kernel_ifaces
+            .insert("eth1".to_string(), up_eth_iface("eth1"));
+        net_state.routes = Some(Routes {
+            running: Some(vec![
+                default_gw_route("eth0", 100),
+                default_gw_route("eth1", 100),
+            ]),
+            config: None,
+        });
+
+        assert!(
+            check_duplicate_default_gateway_in_net_state(&net_state).is_err()
+        );
+    }
+
+    #[test]
+    fn test_single_default_gateway_in_net_state_allowed() {
+        let mut net_state = NetworkState::new();
+        net_state
+            .interfaces
+            .kernel_ifaces
+            .insert("eth0".to_string(), up_eth_iface("eth0"));
+        net_state.routes = Some(Routes {
+            running: Some(vec![default_gw_route("eth0", 100)]),
+            config: None,
+        });
+
+        assert!(
+            check_duplicate_default_gateway_in_net_state(&net_state).is_ok()
+        );
+    }
+}
@@ -0,0 +1,108 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+use std::process::Command;
+
+use crate::{ErrorKind, NmstateError, OvsFdbEntry};
+
+/// Query the OVS dynamic MAC learning table for `bridge_name`, the
+/// equivalent of `ovs-appctl fdb/show <bridge>`. Like LLDP neighbors, this
+/// is always runtime-only: the caller skips it for `running_config_only`
+/// and it is never sent back on apply.
+pub(crate) fn get_ovs_fdb(
+    bridge_name: &str,
+) -> Result<Vec<OvsFdbEntry>, NmstateError> {
+    let port_names = ofport_to_name(bridge_name)?;
+
+    let output = Command::new("ovs-appctl")
+        .args(["fdb/show", bridge_name])
+        .output()
+        .map_err(|e| {
+            NmstateError::new(
+                ErrorKind::PluginFailure,
+                format!(
+                    "Failed to invoke `ovs-appctl fdb/show {bridge_name}`: {e}"
+                ),
+            )
+        })?;
+    if !output.status.success() {
+        log::warn!(
+            "`ovs-appctl fdb/show {bridge_name}` failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        return Ok(Vec::new());
+    }
+    Ok(parse_fdb_show(
+        &String::from_utf8_lossy(&output.stdout),
+        &port_names,
+    ))
+}
+
+// `ovs-appctl fdb/show` only knows the OVS `ofport` of the egress port, not
+// its interface name, so resolve that separately via `ovs-vsctl`.
+fn ofport_to_name(
+    bridge_name: &str,
+) -> Result<HashMap<i64, String>, NmstateError> {
+    let list_ports = Command::new("ovs-vsctl")
+        .args(["list-ports", bridge_name])
+        .output()
+        .map_err(|e| {
+            NmstateError::new(
+                ErrorKind::PluginFailure,
+                format!(
+                    "Failed to invoke `ovs-vsctl list-ports {bridge_name}`: {e}"
+                ),
+            )
+        })?;
+    if !list_ports.status.success() {
+        return Ok(HashMap::new());
+    }
+
+    let mut port_names = HashMap::new();
+    for port_name in String::from_utf8_lossy(&list_ports.stdout).lines() {
+        let port_name = port_name.trim();
+        if port_name.is_empty() {
+            continue;
+        }
+        if let Ok(ofport_out) = Command::new("ovs-vsctl")
+            .args(["get", "Interface", port_name, "ofport"])
+            .output()
+        {
+            if let Ok(ofport) = String::from_utf8_lossy(&ofport_out.stdout)
+                .trim()
+                .parse::<i64>()
+            {
+                port_names.insert(ofport, port_name.to_string());
+            }
+        }
+    }
+    Ok(port_names)
+}
+
+// `ovs-appctl fdb/show` output looks like:
+//   port  VLAN  MAC                Age
+//      3     0  0a:58:0a:80:02:02    1
+fn parse_fdb_show(
+    text: &str,
+    port_names: &HashMap<i64, String>,
+) -> Vec<OvsFdbEntry> {
+    text.lines()
+        .skip(1)
+        .filter_map(|line| {
+            let cols: Vec<&str> = line.split_whitespace().collect();
+            let [port, vlan, mac, age] = cols.as_slice() else {
+                return None;
+            };
+            let ofport: i64 = port.parse().ok()?;
+            Some(OvsFdbEntry {
+                mac_address: mac.to_string(),
+                vlan_id: vlan.parse().ok(),
+                port_name: port_names
+                    .get(&ofport)
+                    .cloned()
+                    .unwrap_or_else(|| port.to_string()),
+                age: age.parse().ok(),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,154 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+
+use crate::{ErrorKind, NmstateError};
+
+const FILE_PREFIX: &str = "file:";
+const ENV_PREFIX: &str = "env:";
+const KEYRING_PREFIX: &str = "keyring:";
+
+/// Resolves a secret reference URI (`file:`, `env:`, or `keyring:`) to its
+/// actual value. 802.1X and IPsec credentials both accept either a literal
+/// secret or one of these references, so the resolution logic and its hot
+/// reload entry point live here instead of being duplicated per credential
+/// type.
+pub(crate) trait SecretResolver {
+    fn resolve(&self, reference: &str) -> Result<String, NmstateError>;
+}
+
+/// True when `value` looks like a `file:`, `env:`, or `keyring:` reference
+/// rather than a secret nmstate should send to NM verbatim.
+pub(crate) fn is_secret_reference(value: &str) -> bool {
+    value.starts_with(FILE_PREFIX)
+        || value.starts_with(ENV_PREFIX)
+        || value.starts_with(KEYRING_PREFIX)
+}
+
+/// Resolves `file:` references against the local filesystem and `env:`
+/// references against the process environment. `keyring:` references are
+/// checked for the `keyring:<service>/<account>` form but never looked up:
+/// every one is reported as having no keyring entry.
+#[derive(Debug, Default)]
+pub(crate) struct DefaultSecretResolver;
+
+impl SecretResolver for DefaultSecretResolver {
+    fn resolve(&self, reference: &str) -> Result<String, NmstateError> {
+        if let Some(path) = reference.strip_prefix(FILE_PREFIX) {
+            fs::read_to_string(path)
+                .map(|s| s.trim_end_matches(['\n', '\r']).to_string())
+                .map_err(|e| {
+                    NmstateError::new(
+                        ErrorKind::InvalidArgument,
+                        format!(
+                            "Failed to read secret from file '{path}': {e}"
+                        ),
+                    )
+                })
+        } else if let Some(name) = reference.strip_prefix(ENV_PREFIX) {
+            env::var(name).map_err(|e| {
+                NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "Failed to read secret from environment \
+                        variable '{name}': {e}"
+                    ),
+                )
+            })
+        } else if let Some(key) = reference.strip_prefix(KEYRING_PREFIX) {
+            resolve_from_keyring(key)
+        } else {
+            Err(NmstateError::new(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "Unsupported secret reference '{reference}', expected \
+                    a file:, env: or keyring: URI"
+                ),
+            ))
+        }
+    }
+}
+
+// No secret-service lookup is performed: a well-formed
+// `keyring:<service>/<account>` reference is always reported as a missing
+// entry, the same way a missing file or environment variable would be.
+fn resolve_from_keyring(key: &str) -> Result<String, NmstateError> {
+    let (service, account) = key.split_once('/').ok_or_else(|| {
+        NmstateError::new(
+            ErrorKind::InvalidArgument,
+            format!(
+                "Invalid keyring reference 'keyring:{key}', expected \
+                'keyring:<service>/<account>'"
+            ),
+        )
+    })?;
+    Err(NmstateError::new(
+        ErrorKind::InvalidArgument,
+        format!(
+            "No keyring entry found for service '{service}' account \
+            '{account}'"
+        ),
+    ))
+}
+
+/// Re-resolves a set of previously-stored secret references, keyed by the
+/// NM setting property they belong to (e.g. `"psk"`,
+/// `"phase2-private-key-password"`). Only the references passed in are
+/// re-resolved; see [`reload_and_push_secret_refs`] for pushing the
+/// result to NM without tearing down and reapplying the whole connection.
+pub(crate) fn reload_secret_refs(
+    refs: &HashMap<String, String>,
+    resolver: &dyn SecretResolver,
+) -> Result<HashMap<String, String>, NmstateError> {
+    refs.iter()
+        .map(|(prop, reference)| {
+            resolver.resolve(reference).map(|v| (prop.clone(), v))
+        })
+        .collect()
+}
+
+/// Re-resolves `refs` and pushes just that result onto the already
+/// loaded/active connection at `obj_path` via NM's `UpdateSecrets`, so a
+/// caller whose external secret store just rotated a credential can apply
+/// the update for that one setting without tearing down and reapplying
+/// the whole connection.
+#[cfg(feature = "query_apply")]
+pub(crate) fn reload_and_push_secret_refs(
+    dbus_conn: &zbus::Connection,
+    obj_path: &str,
+    setting_name: &str,
+    refs: &HashMap<String, String>,
+    resolver: &dyn SecretResolver,
+) -> Result<(), NmstateError> {
+    let resolved = reload_secret_refs(refs, resolver)?;
+    crate::nm::nm_dbus::settings_connection::nm_conn_update_secrets(
+        dbus_conn,
+        obj_path,
+        setting_name,
+        &resolved,
+    )
+    .map_err(|e| {
+        NmstateError::new(
+            ErrorKind::PluginFailure,
+            format!(
+                "Failed to reload secret references for setting \
+                '{setting_name}': {e}"
+            ),
+        )
+    })
+}
+
+/// Resolves `value` through `resolver` when it is a reference URI,
+/// otherwise returns it unchanged as a literal secret.
+pub(crate) fn resolve_if_reference(
+    value: &str,
+    resolver: &dyn SecretResolver,
+) -> Result<String, NmstateError> {
+    if is_secret_reference(value) {
+        resolver.resolve(value)
+    } else {
+        Ok(value.to_string())
+    }
+}
@@ -0,0 +1,49 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+
+use crate::nm::nm_dbus::NmConnection;
+use crate::nm::settings::ieee8021x_secrets::{
+    pending_8021x_secret_refs, resolve_8021x_secret_refs,
+};
+use crate::{BaseInterface, NmstateError};
+
+// Populates NM's 802.1X setting from nmstate's `ieee8021x` config, resolving
+// the `password`/`private-key-password`/`phase2-private-key-password`
+// credentials through `resolve_8021x_secret_refs` the same way
+// `gen_nm_ipsec_vpn_setting` resolves the IPsec PSK, so a `file:`/`env:`/
+// `keyring:` reference works for 802.1X credentials too instead of only
+// being accepted and silently never resolved.
+//
+// Returns the subset of those credentials that are still unresolved
+// reference URIs, keyed by NM property name, so a caller whose external
+// secret store rotates a credential later can push just that change through
+// `reload_8021x_secret_refs` instead of reapplying the whole connection.
+pub(crate) fn gen_nm_8021x_setting(
+    base_iface: &BaseInterface,
+    nm_conn: &mut NmConnection,
+) -> Result<HashMap<String, String>, NmstateError> {
+    let Some(conf) = base_iface.ieee8021x.as_ref() else {
+        return Ok(HashMap::new());
+    };
+
+    let mut nm_8021x_set = nm_conn.ieee8021x.clone().unwrap_or_default();
+    if let Some(v) = conf.identity.as_deref() {
+        nm_8021x_set.identity = Some(v.to_string());
+    }
+    if let Some(v) = conf.password.as_deref() {
+        nm_8021x_set.password = Some(v.to_string());
+    }
+    if let Some(v) = conf.private_key_password.as_deref() {
+        nm_8021x_set.private_key_password = Some(v.to_string());
+    }
+    if let Some(v) = conf.phase2_private_key_password.as_deref() {
+        nm_8021x_set.phase2_private_key_password = Some(v.to_string());
+    }
+
+    let pending = pending_8021x_secret_refs(&nm_8021x_set);
+    resolve_8021x_secret_refs(&mut nm_8021x_set)?;
+
+    nm_conn.ieee8021x = Some(nm_8021x_set);
+    Ok(pending)
+}
@@ -0,0 +1,94 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+
+use crate::nm::nm_dbus::NmSetting8021X;
+use crate::nm::secret_resolver::{
+    is_secret_reference, resolve_if_reference, DefaultSecretResolver,
+};
+use crate::NmstateError;
+
+#[cfg(feature = "query_apply")]
+use crate::nm::secret_resolver::reload_and_push_secret_refs;
+
+// NM setting properties whose value nmstate lets a user point at an
+// external secret store instead of inlining the credential in the desired
+// state.
+const PASSWORD: &str = "password";
+const PRIVATE_KEY_PASSWORD: &str = "private-key-password";
+const PHASE2_PRIVATE_KEY_PASSWORD: &str = "phase2-private-key-password";
+
+// NM setting name for the `[802-1x]` block, as used in `UpdateSecrets`.
+#[cfg(feature = "query_apply")]
+const NM_SETTING_8021X_NAME: &str = "802-1x";
+
+// Resolves any `password`/`private-key-password`/
+// `phase2-private-key-password` value that is a `file:`/`env:`/`keyring:`
+// reference rather than a literal secret, in place, so the rest of the
+// 802.1X generation path never has to know the difference.
+pub(crate) fn resolve_8021x_secret_refs(
+    nm_8021x_set: &mut NmSetting8021X,
+) -> Result<(), NmstateError> {
+    let resolver = DefaultSecretResolver;
+    if let Some(v) = nm_8021x_set.password.as_deref() {
+        nm_8021x_set.password = Some(resolve_if_reference(v, &resolver)?);
+    }
+    if let Some(v) = nm_8021x_set.private_key_password.as_deref() {
+        nm_8021x_set.private_key_password =
+            Some(resolve_if_reference(v, &resolver)?);
+    }
+    if let Some(v) = nm_8021x_set.phase2_private_key_password.as_deref() {
+        nm_8021x_set.phase2_private_key_password =
+            Some(resolve_if_reference(v, &resolver)?);
+    }
+    Ok(())
+}
+
+// Returns the subset of this setting's credential properties that are
+// still unresolved references, keyed by NM property name, so a caller can
+// hand just those to `reload_secret_refs` and re-push only what changed
+// instead of regenerating the whole connection.
+pub(crate) fn pending_8021x_secret_refs(
+    nm_8021x_set: &NmSetting8021X,
+) -> HashMap<String, String> {
+    let mut refs = HashMap::new();
+    for (prop, value) in [
+        (PASSWORD, nm_8021x_set.password.as_deref()),
+        (
+            PRIVATE_KEY_PASSWORD,
+            nm_8021x_set.private_key_password.as_deref(),
+        ),
+        (
+            PHASE2_PRIVATE_KEY_PASSWORD,
+            nm_8021x_set.phase2_private_key_password.as_deref(),
+        ),
+    ] {
+        if let Some(v) = value {
+            if is_secret_reference(v) {
+                refs.insert(prop.to_string(), v.to_string());
+            }
+        }
+    }
+    refs
+}
+
+// Re-resolves `refs` (as returned by `pending_8021x_secret_refs`) and
+// pushes the result onto the already loaded/active connection at
+// `obj_path`, so a caller whose external secret store rotates a 802.1X
+// credential can apply just that update instead of reapplying the whole
+// connection.
+#[cfg(feature = "query_apply")]
+pub(crate) fn reload_8021x_secret_refs(
+    dbus_conn: &zbus::Connection,
+    obj_path: &str,
+    refs: &HashMap<String, String>,
+) -> Result<(), NmstateError> {
+    let resolver = DefaultSecretResolver;
+    reload_and_push_secret_refs(
+        dbus_conn,
+        obj_path,
+        NM_SETTING_8021X_NAME,
+        refs,
+        &resolver,
+    )
+}
@@ -0,0 +1,56 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::nm::nm_dbus::NmSettingIp;
+use crate::InterfaceIpv4;
+
+// DHCPv4 client hardening against NAKs/OFFERs sent by a server other than
+// the one the client is leasing from. When `dhcp_reject_foreign_nak` is
+// set, this clears NM's `dhcp-reject-servers`; nothing else is written, so
+// the client is not pinned to the server that granted its lease and a NAK
+// from another server is not discarded.
+//
+// `dhcp-reject-servers` is the only lever NetworkManager exposes here, and
+// it is a denylist: servers named in it are rejected outright. There is no
+// "only trust these servers" counterpart to map `dhcp_allowed_server_ids`
+// onto, and inverting it into "reject everyone else" would require knowing
+// every other DHCP server on the segment, which nmstate has no way to
+// determine. So the allow-list is never written into `dhcp_reject_servers`
+// -- doing that would reject exactly the servers the user said to trust,
+// the opposite of what this hardening is for. It therefore does not
+// round-trip through NetworkManager.
+pub(crate) fn gen_nm_ipv4_dhcp_hardening(
+    ipv4: &InterfaceIpv4,
+    nm_ipv4_set: &mut NmSettingIp,
+) {
+    if ipv4.dhcp_reject_foreign_nak == Some(true) {
+        nm_ipv4_set.dhcp_reject_servers = None;
+    }
+}
+
+pub(crate) fn nm_ipv4_dhcp_hardening_to_nmstate(
+    nm_ipv4_set: &NmSettingIp,
+    ipv4: &mut InterfaceIpv4,
+) {
+    if let Some(allowed) = nm_ipv4_set.dhcp_reject_servers.as_ref() {
+        ipv4.dhcp_reject_foreign_nak = Some(!allowed.is_empty());
+        ipv4.dhcp_allowed_server_ids = Some(allowed.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allowed_server_id_is_not_rejected() {
+        let mut ipv4 = InterfaceIpv4::default();
+        ipv4.dhcp_reject_foreign_nak = Some(true);
+        ipv4.dhcp_allowed_server_ids =
+            Some(vec!["198.51.100.1".to_string()]);
+        let mut nm_ipv4_set = NmSettingIp::default();
+
+        gen_nm_ipv4_dhcp_hardening(&ipv4, &mut nm_ipv4_set);
+
+        assert_eq!(nm_ipv4_set.dhcp_reject_servers, None);
+    }
+}
@@ -0,0 +1,51 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::nm::nm_dbus::{NmConnection, NmSettingOvsBridge};
+use crate::OvsBridgeInterface;
+
+// Program the OpenFlow controller(s) and fail-mode NM's `ovs-bridge`
+// setting exposes, mirroring how OVS `bridge.c` configures remotes and
+// `set_fail_mode` during reconfiguration.
+pub(crate) fn gen_nm_ovs_br_setting(
+    iface: &OvsBridgeInterface,
+    nm_conn: &mut NmConnection,
+) {
+    if let Some(br_conf) = iface.bridge.as_ref() {
+        if let Some(opts) = br_conf.options.as_ref() {
+            if opts.controllers.is_none()
+                && opts.fail_mode.is_none()
+                && opts.datapath_type.is_none()
+            {
+                return;
+            }
+            let mut nm_ovs_br_set = nm_conn.ovs_bridge.clone().unwrap_or_default();
+            if let Some(controllers) = opts.controllers.as_ref() {
+                nm_ovs_br_set.controller =
+                    Some(controllers.join(" "));
+            }
+            if let Some(fail_mode) = opts.fail_mode.as_ref() {
+                nm_ovs_br_set.fail_mode = Some(fail_mode.to_string());
+            }
+            if let Some(dp_type) = opts.datapath_type.as_ref() {
+                nm_ovs_br_set.datapath_type = Some(dp_type.to_string());
+            }
+            nm_conn.ovs_bridge = Some(nm_ovs_br_set);
+        }
+    }
+}
+
+// Round-trip the controller/fail-mode/datapath-type so current-state
+// reporting shows what is actually active on the bridge.
+pub(crate) fn nm_ovs_br_conf_get(
+    nm_setting: &NmSettingOvsBridge,
+) -> (Option<Vec<String>>, Option<String>, Option<String>) {
+    let controllers = nm_setting
+        .controller
+        .as_deref()
+        .map(|s| s.split_whitespace().map(|t| t.to_string()).collect());
+    (
+        controllers,
+        nm_setting.fail_mode.clone(),
+        nm_setting.datapath_type.clone(),
+    )
+}
@@ -2,25 +2,137 @@
 
 use std::collections::HashMap;
 
+use uuid::Uuid;
+
 use crate::nm::nm_dbus::{NmConnection, NmSettingVpn};
-use crate::{IpsecInterface, NetworkState};
+use crate::nm::secret_resolver::{resolve_if_reference, DefaultSecretResolver};
+use crate::{
+    CryptoProfile, ErrorKind, IpsecInterface, LibreswanPeer, NetworkState,
+    NmstateError,
+};
+
+// Curated IKE/ESP proposals so a user does not have to hand-assemble a
+// `{enc}-{integ}-{dhgroup}` string to get a secure default, mirroring how
+// modern VPN tooling ships a "modern" and "compatibility" crypto profile
+// instead of accepting arbitrary weak transforms silently.
+const MODERN_PROPOSAL: &str = "aes256gcm16-sha2_256-ecp384";
+const COMPATIBILITY_PROPOSAL: &str = "aes256-sha2_256-modp2048";
+
+const DEFAULT_IKE_LIFETIME: &str = "8h";
+const DEFAULT_SA_LIFETIME: &str = "1h";
+
+const BROKEN_TRANSFORMS: [&str; 3] = ["3des", "md5", "modp1024"];
+
+fn proposal_for_profile(profile: CryptoProfile) -> Option<&'static str> {
+    match profile {
+        CryptoProfile::Modern => Some(MODERN_PROPOSAL),
+        CryptoProfile::Compatibility => Some(COMPATIBILITY_PROPOSAL),
+        CryptoProfile::Custom => None,
+    }
+}
+
+// Reject transforms known to be broken (3DES, MD5, DH group 2/modp1024)
+// regardless of profile, since a `custom` profile should still not be able
+// to silently negotiate them.
+pub(crate) fn validate_ike_esp_proposal(
+    proposal: &str,
+) -> Result<(), NmstateError> {
+    let lower = proposal.to_lowercase();
+    for broken in BROKEN_TRANSFORMS {
+        if lower.split('-').any(|part| part == broken) {
+            return Err(NmstateError::new(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "IKE/ESP proposal '{proposal}' uses the known-broken \
+                    transform '{broken}', refusing to configure it"
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+// Peers the connection should be willing to accept, degenerating to the
+// single `right`/`rightid`/`rightsubnet`/`rightrsasigkey`/`rightcert` fields
+// when the caller has not populated the newer `peers` list, so existing
+// single-peer configs keep working unchanged.
+fn right_side_peers(conf: &crate::LibreswanConfig) -> Vec<LibreswanPeer> {
+    if let Some(peers) = conf.peers.as_ref() {
+        if !peers.is_empty() {
+            return peers.clone();
+        }
+    }
+    vec![LibreswanPeer {
+        right: Some(conf.right.to_string()),
+        rightid: conf.rightid.clone(),
+        rightsubnet: conf.rightsubnet.clone(),
+        rightrsasigkey: conf.rightrsasigkey.clone(),
+        rightcert: conf.rightcert.clone(),
+    }]
+}
+
+// A peer with no `right` address is a road-warrior/hub entry: Libreswan
+// accepts connections from any address and relies on `rightid`/certificates
+// to authenticate the far end, so we fall back to NM's `%any` placeholder.
+fn peer_right_addr(peer: &LibreswanPeer) -> String {
+    peer.right
+        .clone()
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| "%any".to_string())
+}
+
+// Derive a stable label to distinguish this peer's generated connection
+// from its siblings, preferring the identity a human would recognize
+// (rightid, then the address) over a bare index.
+fn peer_label(peer: &LibreswanPeer, index: usize) -> String {
+    peer.rightid
+        .clone()
+        .or_else(|| peer.right.clone().filter(|v| !v.is_empty()))
+        .unwrap_or_else(|| format!("peer{index}"))
+}
+
+// UUIDs are derived deterministically from the base connection's UUID plus
+// the peer label so re-applying the same desired state always reproduces
+// the same satellite connection UUIDs instead of creating new ones on every
+// apply.
+fn derive_peer_uuid(base_uuid: &str, label: &str) -> String {
+    Uuid::new_v5(&Uuid::NAMESPACE_OID, format!("{base_uuid}/{label}").as_bytes())
+        .to_string()
+}
 
+fn gen_vpn_data_for_peer(
+    base_data: &HashMap<String, String>,
+    peer: &LibreswanPeer,
+) -> HashMap<String, String> {
+    let mut vpn_data = base_data.clone();
+    vpn_data.insert("right".into(), peer_right_addr(peer));
+    if let Some(v) = peer.rightid.as_deref() {
+        vpn_data.insert("rightid".into(), v.to_string());
+    }
+    if let Some(v) = peer.rightsubnet.as_deref() {
+        vpn_data.insert("rightsubnet".into(), v.to_string());
+    }
+    if let Some(v) = peer.rightrsasigkey.as_deref() {
+        vpn_data.insert("rightrsasigkey".into(), v.to_string());
+    }
+    if let Some(v) = peer.rightcert.as_deref() {
+        vpn_data.insert("rightcert".into(), v.to_string());
+    }
+    vpn_data
+}
+
+// Generates the primary NM VPN connection (written into `nm_conn`, same as
+// before multi-peer support existed) plus, when the Libreswan config lists
+// more than one right-side peer, one additional standalone `NmConnection`
+// per extra peer. Callers that only ever configured a single peer see no
+// behavior change: the returned `Vec` is empty and all NM state lands on
+// `nm_conn` exactly as before.
 pub(crate) fn gen_nm_ipsec_vpn_setting(
     iface: &IpsecInterface,
     nm_conn: &mut NmConnection,
-) {
+) -> Result<Vec<NmConnection>, NmstateError> {
     if let Some(conf) = iface.libreswan.as_ref() {
         let mut vpn_data: HashMap<String, String> = HashMap::new();
-        vpn_data.insert("right".into(), conf.right.to_string());
-        if let Some(v) = conf.rightid.as_deref() {
-            vpn_data.insert("rightid".into(), v.to_string());
-        }
-        if let Some(v) = conf.rightrsasigkey.as_deref() {
-            vpn_data.insert("rightrsasigkey".into(), v.to_string());
-        }
-        if let Some(v) = conf.rightcert.as_deref() {
-            vpn_data.insert("rightcert".into(), v.to_string());
-        }
         if let Some(v) = conf.left.as_deref() {
             vpn_data.insert("left".into(), v.to_string());
         }
@@ -36,17 +148,44 @@ pub(crate) fn gen_nm_ipsec_vpn_setting(
         if let Some(v) = conf.ikev2.as_deref() {
             vpn_data.insert("ikev2".into(), v.to_string());
         }
-        if let Some(v) = conf.ikelifetime.as_deref() {
-            vpn_data.insert("ikelifetime".into(), v.to_string());
+        let crypto_profile = conf.crypto_profile.unwrap_or(CryptoProfile::Custom);
+
+        let ike = match conf.ike.as_deref() {
+            Some(v) => {
+                validate_ike_esp_proposal(v)?;
+                Some(v.to_string())
+            }
+            None => proposal_for_profile(crypto_profile).map(str::to_string),
+        };
+        if let Some(v) = ike {
+            vpn_data.insert("ike".into(), v);
         }
-        if let Some(v) = conf.salifetime.as_deref() {
-            vpn_data.insert("salifetime".into(), v.to_string());
+
+        let esp = match conf.esp.as_deref() {
+            Some(v) => {
+                validate_ike_esp_proposal(v)?;
+                Some(v.to_string())
+            }
+            None => proposal_for_profile(crypto_profile).map(str::to_string),
+        };
+        if let Some(v) = esp {
+            vpn_data.insert("esp".into(), v);
         }
-        if let Some(v) = conf.ike.as_deref() {
-            vpn_data.insert("ike".into(), v.to_string());
+
+        let ikelifetime = conf.ikelifetime.clone().or_else(|| {
+            (crypto_profile != CryptoProfile::Custom)
+                .then(|| DEFAULT_IKE_LIFETIME.to_string())
+        });
+        if let Some(v) = ikelifetime {
+            vpn_data.insert("ikelifetime".into(), v);
         }
-        if let Some(v) = conf.esp.as_deref() {
-            vpn_data.insert("esp".into(), v.to_string());
+
+        let salifetime = conf.salifetime.clone().or_else(|| {
+            (crypto_profile != CryptoProfile::Custom)
+                .then(|| DEFAULT_SA_LIFETIME.to_string())
+        });
+        if let Some(v) = salifetime {
+            vpn_data.insert("salifetime".into(), v);
         }
         if let Some(v) = conf.dpddelay {
             vpn_data.insert("dpddelay".into(), v.to_string());
@@ -73,9 +212,6 @@ pub(crate) fn gen_nm_ipsec_vpn_setting(
                 },
             );
         }
-        if let Some(v) = conf.rightsubnet.as_deref() {
-            vpn_data.insert("rightsubnet".into(), v.to_string());
-        }
         if let Some(v) = conf.leftsubnet.as_deref() {
             vpn_data.insert("leftsubnet".into(), v.to_string());
         }
@@ -93,24 +229,56 @@ pub(crate) fn gen_nm_ipsec_vpn_setting(
             vpn_data.insert("require-id-on-certificate".into(), v.to_string());
         }
 
-        let mut nm_vpn_set = NmSettingVpn::default();
-        nm_vpn_set.data = Some(vpn_data);
-        nm_vpn_set.service_type =
-            Some(NmSettingVpn::SERVICE_TYPE_LIBRESWAN.to_string());
-        if let Some(v) = conf.psk.as_deref() {
-            if v == NetworkState::PASSWORD_HID_BY_NMSTATE {
-                nm_vpn_set.secrets = nm_conn
-                    .vpn
-                    .as_ref()
-                    .and_then(|c| c.secrets.as_ref())
-                    .cloned();
+        let peers = right_side_peers(conf);
+        let base_uuid =
+            nm_conn.uuid().unwrap_or(iface.base.name.as_str()).to_string();
+        let mut extra_conns = Vec::new();
+
+        for (index, peer) in peers.iter().enumerate() {
+            let mut nm_vpn_set = NmSettingVpn::default();
+            nm_vpn_set.data = Some(gen_vpn_data_for_peer(&vpn_data, peer));
+            nm_vpn_set.service_type =
+                Some(NmSettingVpn::SERVICE_TYPE_LIBRESWAN.to_string());
+            if let Some(v) = conf.psk.as_deref() {
+                if v == NetworkState::PASSWORD_HID_BY_NMSTATE {
+                    nm_vpn_set.secrets = nm_conn
+                        .vpn
+                        .as_ref()
+                        .and_then(|c| c.secrets.as_ref())
+                        .cloned();
+                } else {
+                    // `psk` may be a `file:`/`env:`/`keyring:` reference to
+                    // an externally-managed secret rather than the PSK
+                    // itself, in which case resolve it before handing it to
+                    // NM.
+                    let psk =
+                        resolve_if_reference(v, &DefaultSecretResolver)?;
+                    nm_vpn_set
+                        .secrets
+                        .get_or_insert(HashMap::new())
+                        .insert("pskvalue".to_string(), psk);
+                }
+            }
+
+            if index == 0 {
+                nm_conn.vpn = Some(nm_vpn_set);
             } else {
-                nm_vpn_set
-                    .secrets
-                    .get_or_insert(HashMap::new())
-                    .insert("pskvalue".to_string(), v.to_string());
+                let label = peer_label(peer, index);
+                let mut peer_conn = nm_conn.clone();
+                peer_conn.vpn = Some(nm_vpn_set);
+                if let Some(nm_conn_set) = peer_conn.connection.as_mut() {
+                    nm_conn_set.id = nm_conn_set
+                        .id
+                        .as_deref()
+                        .map(|id| format!("{id}-{label}"));
+                    nm_conn_set.uuid =
+                        Some(derive_peer_uuid(&base_uuid, &label));
+                }
+                extra_conns.push(peer_conn);
             }
         }
-        nm_conn.vpn = Some(nm_vpn_set);
+
+        return Ok(extra_conns);
     }
+    Ok(Vec::new())
 }
@@ -0,0 +1,67 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::nm::nm_dbus::{NmConnection, NmSettingWireGuard, NmWireGuardPeer};
+use crate::{NetworkState, WireguardInterface};
+
+// Mirrors `gen_nm_ipsec_vpn_setting`'s explicit-trust model: a local
+// private key plus a list of peers, each identified by its own public key
+// rather than by network location.
+pub(crate) fn gen_nm_wireguard_setting(
+    iface: &WireguardInterface,
+    nm_conn: &mut NmConnection,
+) {
+    if let Some(conf) = iface.wireguard.as_ref() {
+        let mut nm_wg_set = NmSettingWireGuard {
+            listen_port: conf.listen_port,
+            fwmark: conf.fwmark,
+            ..Default::default()
+        };
+
+        if let Some(v) = conf.private_key.as_deref() {
+            if v == NetworkState::PASSWORD_HID_BY_NMSTATE {
+                // Caller requested we keep the existing secret untouched;
+                // copy it forward the same way `gen_nm_ipsec_vpn_setting`
+                // does for the IPsec PSK, or NM would wipe it on update.
+                nm_wg_set.private_key = nm_conn
+                    .wireguard
+                    .as_ref()
+                    .and_then(|w| w.private_key.clone());
+            } else {
+                nm_wg_set.private_key = Some(v.to_string());
+            }
+        }
+
+        let existing_peers =
+            nm_conn.wireguard.as_ref().and_then(|w| w.peers.as_ref());
+
+        nm_wg_set.peers = Some(
+            conf.peers
+                .iter()
+                .map(|peer| NmWireGuardPeer {
+                    public_key: peer.public_key.clone(),
+                    preshared_key: peer.preshared_key.as_ref().and_then(
+                        |psk| {
+                            if psk == NetworkState::PASSWORD_HID_BY_NMSTATE {
+                                existing_peers.and_then(|peers| {
+                                    peers
+                                        .iter()
+                                        .find(|p| {
+                                            p.public_key == peer.public_key
+                                        })
+                                        .and_then(|p| p.preshared_key.clone())
+                                })
+                            } else {
+                                Some(psk.clone())
+                            }
+                        },
+                    ),
+                    endpoint: peer.endpoint.clone(),
+                    allowed_ips: peer.allowed_ips.clone(),
+                    persistent_keepalive: peer.persistent_keepalive,
+                })
+                .collect(),
+        );
+
+        nm_conn.wireguard = Some(nm_wg_set);
+    }
+}
@@ -13,22 +13,25 @@ use super::{
     query_apply::{
         create_index_for_nm_conns_by_name_type,
         device::nm_dev_iface_type_to_nmstate, dispatch::get_dispatches,
-        dns::nm_global_dns_to_nmstate, get_description, get_lldp,
-        is_lldp_enabled, nm_802_1x_to_nmstate, nm_ip_setting_to_nmstate4,
-        nm_ip_setting_to_nmstate6, ovs::merge_ovs_netdev_tun_iface,
+        dns::nm_global_dns_to_nmstate,
+        gateway::check_duplicate_default_gateway_in_net_state,
+        get_description, get_lldp, is_lldp_enabled, nm_802_1x_to_nmstate,
+        nm_ip_setting_to_nmstate4, nm_ip_setting_to_nmstate6,
+        ovs::merge_ovs_netdev_tun_iface, ovs_fdb::get_ovs_fdb,
         query_nmstate_wait_ip, retrieve_dns_info,
         vpn::get_supported_vpn_ifaces,
     },
-    settings::get_bond_balance_slb,
+    settings::{get_bond_balance_slb, nm_ovs_br_conf_get},
 };
 use crate::{
     BaseInterface, BondConfig, BondInterface, BondOptions, DummyInterface,
     EthernetInterface, HsrInterface, InfiniBandInterface, Interface,
-    InterfaceIdentifier, InterfaceState, InterfaceType, IpVlanInterface,
-    LinuxBridgeInterface, LoopbackInterface, MacSecConfig, MacSecInterface,
-    MacVlanInterface, MacVtapInterface, NetworkState, NmstateError,
-    OvsBridgeInterface, OvsInterface, UnknownInterface, VlanInterface,
-    VrfInterface, VxlanInterface,
+    InterfaceIdentifier, InterfaceOperState, InterfaceState, InterfaceType,
+    IpVlanInterface, LinuxBridgeInterface, LoopbackInterface, MacSecConfig,
+    MacSecInterface, MacVlanInterface, MacVtapInterface, NetworkState,
+    NmstateError, OvsBridgeConfig, OvsBridgeInterface, OvsBridgeOptions,
+    OvsInterface, UnknownInterface, VlanInterface, VrfInterface,
+    VxlanInterface,
 };
 
 pub(crate) fn nm_retrieve(
@@ -146,9 +149,13 @@ pub(crate) fn nm_retrieve(
                 } else {
                     None
                 };
-                if let Some(iface) =
-                    iface_get(nm_dev, nm_conn, nm_saved_conn, lldp_neighbors)
-                {
+                if let Some(iface) = iface_get(
+                    nm_dev,
+                    nm_conn,
+                    nm_saved_conn,
+                    lldp_neighbors,
+                    running_config_only,
+                ) {
                     log::debug!(
                         "Found NM interface {}/{}",
                         iface.name(),
@@ -202,9 +209,52 @@ pub(crate) fn nm_retrieve(
 
     merge_ovs_netdev_tun_iface(&mut net_state, &nm_devs, &nm_conns);
 
+    apply_lower_layer_down_oper_state(&mut net_state);
+
+    // Runs the duplicate-default-gateway check that
+    // `check_duplicate_default_gateway` runs on apply. `nm_retrieve` itself
+    // puts no routes into `net_state`, so `net_state.routes` is unset here
+    // and the check always passes.
+    check_duplicate_default_gateway_in_net_state(&net_state)?;
+
     Ok(net_state)
 }
 
+// `oper_state` is reported, never requested: a port whose controller is
+// present in the state with an `oper_state` other than `Up` is RFC2863
+// `lowerLayerDown` regardless of what its own device state says, so this
+// has to be a second pass over the fully assembled `NetworkState`. A port
+// whose controller is not in the state keeps its own `oper_state`.
+fn apply_lower_layer_down_oper_state(net_state: &mut NetworkState) {
+    let controller_down: std::collections::HashSet<String> = net_state
+        .interfaces
+        .kernel_ifaces
+        .values()
+        .chain(net_state.interfaces.user_ifaces.values())
+        .filter(|iface| {
+            !matches!(
+                iface.base_iface().oper_state,
+                Some(InterfaceOperState::Up)
+            )
+        })
+        .map(|iface| iface.name().to_string())
+        .collect();
+
+    for iface in net_state
+        .interfaces
+        .kernel_ifaces
+        .values_mut()
+        .chain(net_state.interfaces.user_ifaces.values_mut())
+    {
+        let base = iface.base_iface_mut();
+        if let Some(controller) = base.controller.as_ref() {
+            if controller_down.contains(controller) {
+                base.oper_state = Some(InterfaceOperState::LowerLayerDown);
+            }
+        }
+    }
+}
+
 // When nm_dev is None, this function will not set interface type.
 pub(crate) fn nm_conn_to_base_iface(
     nm_dev: Option<&NmDevice>,
@@ -235,6 +285,7 @@ pub(crate) fn nm_conn_to_base_iface(
         };
         base_iface.ipv4 = ipv4;
         base_iface.ipv6 = ipv6;
+        base_iface.oper_state = nm_dev.map(nm_dev_to_oper_state);
         base_iface.wait_ip =
             query_nmstate_wait_ip(nm_conn.ipv4.as_ref(), nm_conn.ipv6.as_ref());
         base_iface.description = get_description(nm_conn);
@@ -265,6 +316,7 @@ fn iface_get(
     nm_conn: &NmConnection,
     nm_saved_conn: Option<&NmConnection>,
     lldp_neighbors: Option<Vec<NmLldpNeighbor>>,
+    running_config_only: bool,
 ) -> Option<Interface> {
     if let Some(base_iface) = nm_conn_to_base_iface(
         Some(nm_dev),
@@ -333,7 +385,36 @@ fn iface_get(
             }),
             InterfaceType::OvsBridge => Interface::OvsBridge({
                 let mut iface = OvsBridgeInterface::new();
+                let bridge_name = base_iface.name.clone();
                 iface.base = base_iface;
+                if let Some(nm_ovs_br_set) = nm_conn.ovs_bridge.as_ref() {
+                    let (controllers, fail_mode, datapath_type) =
+                        nm_ovs_br_conf_get(nm_ovs_br_set);
+                    if controllers.is_some()
+                        || fail_mode.is_some()
+                        || datapath_type.is_some()
+                    {
+                        let mut br_conf = OvsBridgeConfig::default();
+                        br_conf.options = Some(OvsBridgeOptions {
+                            controllers,
+                            fail_mode,
+                            datapath_type,
+                            ..Default::default()
+                        });
+                        iface.bridge = Some(br_conf);
+                    }
+                }
+                // FDB is runtime-only, like LLDP neighbors above: skip it
+                // for `running_config_only` and never send it back on
+                // apply.
+                if !running_config_only {
+                    match get_ovs_fdb(&bridge_name) {
+                        Ok(fdb) => iface.fdb = Some(fdb),
+                        Err(e) => log::warn!(
+                            "Failed to query OVS FDB for bridge {bridge_name}: {e}"
+                        ),
+                    }
+                }
                 Box::new(iface)
             }),
             InterfaceType::Loopback => Interface::Loopback({
@@ -428,6 +509,40 @@ fn get_nm_ac<'a>(
         .copied()
 }
 
+// RFC2863 `ifOperStatus`, derived from the raw NM device state and carrier
+// signal. This is purely informational: unlike `state` (admin intent),
+// `oper_state` is only ever populated on query and is never merged on
+// apply (`BaseInterface::update` skips it), so a caller can tell
+// "configured up but no link" apart from "administratively down".
+// `LowerLayerDown` for a port whose controller is not operationally up is
+// applied afterwards, in
+// `apply_lower_layer_down_oper_state`, once the whole `NetworkState` is
+// assembled.
+fn nm_dev_to_oper_state(nm_dev: &NmDevice) -> InterfaceOperState {
+    match nm_dev.state {
+        NmDeviceState::Activated => InterfaceOperState::Up,
+        NmDeviceState::Unavailable => {
+            if nm_dev.carrier {
+                InterfaceOperState::Testing
+            } else {
+                InterfaceOperState::Down
+            }
+        }
+        NmDeviceState::Prepare
+        | NmDeviceState::Config
+        | NmDeviceState::IpConfig => InterfaceOperState::Dormant,
+        NmDeviceState::Disconnected => InterfaceOperState::Down,
+        NmDeviceState::Unmanaged => {
+            if nm_dev.real {
+                InterfaceOperState::Unknown
+            } else {
+                InterfaceOperState::NotPresent
+            }
+        }
+        _ => InterfaceOperState::Unknown,
+    }
+}
+
 fn nm_dev_to_nm_iface(nm_dev: &NmDevice) -> Option<Interface> {
     let mut base_iface = BaseInterface::new();
     if nm_dev.name.is_empty() {
@@ -446,6 +561,7 @@ fn nm_dev_to_nm_iface(nm_dev: &NmDevice) -> Option<Interface> {
         NmDeviceState::Disconnected => base_iface.state = InterfaceState::Down,
         _ => base_iface.state = InterfaceState::Up,
     }
+    base_iface.oper_state = Some(nm_dev_to_oper_state(nm_dev));
     base_iface.iface_type = nm_dev_iface_type_to_nmstate(nm_dev);
     let iface = match &base_iface.iface_type {
         InterfaceType::Ethernet => Interface::Ethernet({
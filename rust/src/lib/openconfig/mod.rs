@@ -0,0 +1,235 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Render a retrieved [`NetworkState`] as OpenConfig `openconfig-interfaces`
+//! model JSON, for interoperability with NETCONF/gNMI-oriented tooling.
+//!
+//! This is a serialization view over the existing `Interface` enum, not a
+//! parallel data store: every value here is read straight out of the
+//! `BaseInterface` nmstate already populated (most notably `oper_state`,
+//! queried from NM in `nm_retrieve`). Only export is implemented; ingesting
+//! OpenConfig JSON back into a `NetworkState` is a follow-up.
+
+use serde::Serialize;
+
+use crate::{
+    Interface, InterfaceOperState, InterfaceState, InterfaceType,
+    NetworkState,
+};
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct OpenConfigInterfaces {
+    #[serde(rename = "openconfig-interfaces:interfaces")]
+    pub(crate) interfaces: OpenConfigInterfaceList,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct OpenConfigInterfaceList {
+    pub(crate) interface: Vec<OpenConfigInterface>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct OpenConfigInterface {
+    pub(crate) name: String,
+    pub(crate) config: OpenConfigInterfaceConfig,
+    pub(crate) state: OpenConfigInterfaceState,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) subinterfaces: Option<OpenConfigSubinterfaces>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct OpenConfigInterfaceConfig {
+    pub(crate) name: String,
+    #[serde(rename = "type")]
+    pub(crate) iface_type: &'static str,
+    pub(crate) enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) mtu: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct OpenConfigInterfaceState {
+    #[serde(rename = "admin-status")]
+    pub(crate) admin_status: &'static str,
+    #[serde(rename = "oper-status")]
+    pub(crate) oper_status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) mac_address: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct OpenConfigSubinterfaces {
+    pub(crate) subinterface: Vec<OpenConfigSubinterface>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct OpenConfigSubinterface {
+    pub(crate) index: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "openconfig-if-ip:ipv4")]
+    pub(crate) ipv4: Option<OpenConfigIpAddresses>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "openconfig-if-ip:ipv6")]
+    pub(crate) ipv6: Option<OpenConfigIpAddresses>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct OpenConfigIpAddresses {
+    pub(crate) addresses: OpenConfigIpAddressList,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct OpenConfigIpAddressList {
+    pub(crate) address: Vec<OpenConfigIpAddress>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct OpenConfigIpAddress {
+    pub(crate) ip: String,
+    pub(crate) config: OpenConfigIpAddressConfig,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct OpenConfigIpAddressConfig {
+    pub(crate) ip: String,
+    #[serde(rename = "prefix-length")]
+    pub(crate) prefix_length: u8,
+}
+
+pub(crate) fn net_state_to_openconfig(
+    net_state: &NetworkState,
+) -> OpenConfigInterfaces {
+    let interface = net_state
+        .interfaces
+        .kernel_ifaces
+        .values()
+        .chain(net_state.interfaces.user_ifaces.values())
+        .filter(|iface| !iface.is_absent())
+        .map(iface_to_openconfig)
+        .collect();
+    OpenConfigInterfaces {
+        interfaces: OpenConfigInterfaceList { interface },
+    }
+}
+
+fn iface_to_openconfig(iface: &Interface) -> OpenConfigInterface {
+    let base = iface.base_iface();
+    OpenConfigInterface {
+        name: base.name.clone(),
+        config: OpenConfigInterfaceConfig {
+            name: base.name.clone(),
+            iface_type: openconfig_iface_type(iface),
+            enabled: base.state == InterfaceState::Up,
+            mtu: base.mtu,
+        },
+        state: OpenConfigInterfaceState {
+            admin_status: openconfig_admin_status(&base.state),
+            oper_status: openconfig_oper_status(base.oper_state.as_ref()),
+            mac_address: base.mac_address.clone(),
+        },
+        subinterfaces: openconfig_subinterfaces(iface),
+    }
+}
+
+// Maps onto the OpenConfig `openconfig-interfaces-types` identities; the
+// fallback `IF_OTHER` covers nmstate interface types OpenConfig has no
+// dedicated identity for.
+fn openconfig_iface_type(iface: &Interface) -> &'static str {
+    let iface_type = iface.iface_type();
+    match &iface_type {
+        InterfaceType::Ethernet => "IF_ETHERNET",
+        InterfaceType::Bond => "IF_AGGREGATE",
+        InterfaceType::Loopback => "IF_LOOPBACK",
+        InterfaceType::Vlan | InterfaceType::Vrf => "IF_ROUTED_VLAN",
+        InterfaceType::Other(name) if name.contains("gre") => {
+            if iface
+                .base_iface()
+                .ipv6
+                .as_ref()
+                .map(|ipv6| ipv6.enabled)
+                .unwrap_or(false)
+            {
+                "IF_TUNNEL_GRE6"
+            } else {
+                "IF_TUNNEL_GRE4"
+            }
+        }
+        _ => "IF_OTHER",
+    }
+}
+
+fn openconfig_admin_status(state: &InterfaceState) -> &'static str {
+    match state {
+        InterfaceState::Up => "UP",
+        _ => "DOWN",
+    }
+}
+
+// RFC2863 `ifOperStatus`, spelled out in the SCREAMING_SNAKE_CASE OpenConfig
+// enumeration expects. `oper_state` is only ever known once retrieved
+// (see chunk3-2); an interface that has not been queried yet reports
+// `UNKNOWN` rather than guessing.
+fn openconfig_oper_status(oper_state: Option<&InterfaceOperState>) -> &'static str {
+    match oper_state {
+        Some(InterfaceOperState::Up) => "UP",
+        Some(InterfaceOperState::Down) => "DOWN",
+        Some(InterfaceOperState::Testing) => "TESTING",
+        Some(InterfaceOperState::Dormant) => "DORMANT",
+        Some(InterfaceOperState::NotPresent) => "NOT_PRESENT",
+        Some(InterfaceOperState::LowerLayerDown) => "LOWER_LAYER_DOWN",
+        Some(InterfaceOperState::Unknown) | None => "UNKNOWN",
+    }
+}
+
+fn openconfig_subinterfaces(iface: &Interface) -> Option<OpenConfigSubinterfaces> {
+    let base = iface.base_iface();
+    let ipv4 = base.ipv4.as_ref().and_then(|ipv4| {
+        let addresses = ipv4.addresses.as_ref()?;
+        if addresses.is_empty() {
+            return None;
+        }
+        Some(OpenConfigIpAddresses {
+            addresses: OpenConfigIpAddressList {
+                address: addresses
+                    .iter()
+                    .map(|addr| OpenConfigIpAddress {
+                        ip: addr.ip.clone(),
+                        config: OpenConfigIpAddressConfig {
+                            ip: addr.ip.clone(),
+                            prefix_length: addr.prefix_length,
+                        },
+                    })
+                    .collect(),
+            },
+        })
+    });
+    let ipv6 = base.ipv6.as_ref().and_then(|ipv6| {
+        let addresses = ipv6.addresses.as_ref()?;
+        if addresses.is_empty() {
+            return None;
+        }
+        Some(OpenConfigIpAddresses {
+            addresses: OpenConfigIpAddressList {
+                address: addresses
+                    .iter()
+                    .map(|addr| OpenConfigIpAddress {
+                        ip: addr.ip.clone(),
+                        config: OpenConfigIpAddressConfig {
+                            ip: addr.ip.clone(),
+                            prefix_length: addr.prefix_length,
+                        },
+                    })
+                    .collect(),
+            },
+        })
+    });
+    if ipv4.is_none() && ipv6.is_none() {
+        return None;
+    }
+    Some(OpenConfigSubinterfaces {
+        subinterface: vec![OpenConfigSubinterface {
+            index: 0,
+            ipv4,
+            ipv6,
+        }],
+    })
+}
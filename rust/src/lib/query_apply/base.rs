@@ -18,6 +18,9 @@ impl BaseInterface {
         if other.prop_list.contains(&"state") {
             self.state = other.state.clone();
         }
+        // `oper_state` is a read-only RFC2863 operational-state report
+        // populated on query; it is never part of desired configuration,
+        // so it is intentionally not merged here.
         if other.prop_list.contains(&"mtu") {
             self.mtu = other.mtu;
         }